@@ -0,0 +1,115 @@
+//! Stands in for a model checker that streams its exploration to Graphew.
+//!
+//! Explores the water jug puzzle breadth first and sends every state and
+//! transition as soon as it is found. Start Graphew with
+//! `graphew --live tcp:127.0.0.1:7878` (or use `File > Listen…`) and run
+//!
+//! ```text
+//! cargo run --example live_client -- tcp:127.0.0.1:7878
+//! ```
+//!
+//! The target may also be `unix:<path>`, or `-` to write to the standard
+//! output, e.g. `cargo run --example live_client -- - | graphew --live -`.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, Write},
+    net::TcpStream,
+    thread,
+    time::Duration,
+};
+
+use serde_json::json;
+
+/// Capacities of the two jugs.
+const CAPACITY: [u32; 2] = [7, 11];
+/// Amount of water to measure.
+const GOAL: u32 = 4;
+/// Time between two states, to watch the exploration happen.
+const DELAY: Duration = Duration::from_millis(100);
+
+fn main() -> io::Result<()> {
+    let target = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "tcp:127.0.0.1:7878".to_string());
+    let mut out: Box<dyn Write> = match target.split_once(':') {
+        _ if target == "-" => Box::new(io::stdout()),
+        Some(("tcp", address)) => Box::new(TcpStream::connect(address)?),
+        #[cfg(unix)]
+        Some(("unix", path)) => Box::new(std::os::unix::net::UnixStream::connect(path)?),
+        _ => {
+            eprintln!("usage: live_client [tcp:<address> | unix:<path> | -]");
+            std::process::exit(2);
+        }
+    };
+
+    let mut ids = HashMap::new();
+    let mut queue = VecDeque::new();
+    let initial = [0, 0];
+    ids.insert(initial, 0);
+    queue.push_back(initial);
+    send_state(&mut out, 0, initial, true)?;
+
+    while let Some(jugs) = queue.pop_front() {
+        let from = ids[&jugs];
+        for (label, next) in moves(jugs) {
+            let id = match ids.get(&next) {
+                Some(id) => *id,
+                None => {
+                    let id = ids.len();
+                    ids.insert(next, id);
+                    queue.push_back(next);
+                    send_state(&mut out, id, next, false)?;
+                    thread::sleep(DELAY);
+                    id
+                }
+            };
+            send(
+                &mut out,
+                json!({ "type": "transition", "from": from, "to": id, "label": label }),
+            )?;
+        }
+    }
+    send(&mut out, json!({ "type": "done" }))
+}
+
+fn send_state(out: &mut dyn Write, id: usize, jugs: [u32; 2], initial: bool) -> io::Result<()> {
+    let props = match jugs.contains(&GOAL) {
+        true => vec!["goal"],
+        false => vec![],
+    };
+    send(
+        out,
+        json!({
+            "type": "state",
+            "id": id,
+            "info": format!("a = {}, b = {}", jugs[0], jugs[1]),
+            "props": props,
+            "initial": initial,
+            "accepting": jugs.contains(&GOAL),
+        }),
+    )
+}
+
+fn send(out: &mut dyn Write, message: serde_json::Value) -> io::Result<()> {
+    writeln!(out, "{}", message)?;
+    out.flush()
+}
+
+/// Successors of `jugs` with the name of the move leading to them.
+fn moves([a, b]: [u32; 2]) -> Vec<(&'static str, [u32; 2])> {
+    let [max_a, max_b] = CAPACITY;
+    let a_to_b = a.min(max_b - b);
+    let b_to_a = b.min(max_a - a);
+    [
+        ("fill a", [max_a, b]),
+        ("fill b", [a, max_b]),
+        ("empty a", [0, b]),
+        ("empty b", [a, 0]),
+        ("pour a into b", [a - a_to_b, b + a_to_b]),
+        ("pour b into a", [a + b_to_a, b - b_to_a]),
+    ]
+    .into_iter()
+    .filter(|(_, next)| *next != [a, b])
+    .collect()
+}
@@ -0,0 +1,4 @@
+pub mod ctl;
+pub mod deadlock;
+pub mod path;
+pub mod scc;
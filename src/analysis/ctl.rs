@@ -0,0 +1,531 @@
+use std::{collections::VecDeque, fmt};
+
+use egui_graphs::Graph;
+use petgraph::{
+    stable_graph::NodeIndex,
+    visit::{EdgeRef, IntoEdgeReferences, NodeIndexable},
+    Directed,
+};
+
+use crate::graph::{state::State, transition::Transition};
+
+/// A CTL formula over the atomic propositions of the states.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Formula {
+    True,
+    False,
+    Prop(String),
+    Not(Box<Formula>),
+    And(Box<Formula>, Box<Formula>),
+    Or(Box<Formula>, Box<Formula>),
+    Implies(Box<Formula>, Box<Formula>),
+    EX(Box<Formula>),
+    AX(Box<Formula>),
+    EF(Box<Formula>),
+    AF(Box<Formula>),
+    EG(Box<Formula>),
+    AG(Box<Formula>),
+    EU(Box<Formula>, Box<Formula>),
+    AU(Box<Formula>, Box<Formula>),
+}
+
+impl fmt::Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Formula::True => write!(f, "true"),
+            Formula::False => write!(f, "false"),
+            Formula::Prop(name) if is_plain(name) => write!(f, "{}", name),
+            Formula::Prop(name) => write!(f, "\"{}\"", name),
+            Formula::Not(a) => write!(f, "!{}", a),
+            Formula::And(a, b) => write!(f, "({} & {})", a, b),
+            Formula::Or(a, b) => write!(f, "({} | {})", a, b),
+            Formula::Implies(a, b) => write!(f, "({} -> {})", a, b),
+            Formula::EX(a) => write!(f, "EX {}", a),
+            Formula::AX(a) => write!(f, "AX {}", a),
+            Formula::EF(a) => write!(f, "EF {}", a),
+            Formula::AF(a) => write!(f, "AF {}", a),
+            Formula::EG(a) => write!(f, "EG {}", a),
+            Formula::AG(a) => write!(f, "AG {}", a),
+            Formula::EU(a, b) => write!(f, "E[{} U {}]", a, b),
+            Formula::AU(a, b) => write!(f, "A[{} U {}]", a, b),
+        }
+    }
+}
+
+/// Whether a proposition can be written without quotes.
+fn is_plain(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(is_word_char)
+        && !matches!(
+            name,
+            "true" | "false" | "EX" | "AX" | "EF" | "AF" | "EG" | "AG" | "E" | "A" | "U"
+        )
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.'
+}
+
+/// Error produced when a formula cannot be parsed. Columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CtlError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CtlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for CtlError {}
+
+/// Parses a formula such as `AG (req -> AF ack)` or `E[!err U done]`.
+///
+/// `!`, `&`, `|` and `->` are the boolean connectives, from the tightest to
+/// the loosest, and `->` groups to the right. Propositions that are not plain
+/// words or clash with an operator are written in double quotes.
+pub fn parse(text: &str) -> Result<Formula, CtlError> {
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        pos: 0,
+    };
+    let formula = parser.implies()?;
+    parser.skip_whitespace();
+    match parser.peek() {
+        None => Ok(formula),
+        Some(c) => Err(parser.error(format!("unexpected `{}`", c))),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> CtlError {
+        CtlError {
+            column: self.pos + 1,
+            message: message.into(),
+        }
+    }
+
+    /// Consumes `token` if the formula continues with it.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let end = self.pos + token.chars().count();
+        if end <= self.chars.len() && self.chars[self.pos..end].iter().copied().eq(token.chars()) {
+            self.pos = end;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), CtlError> {
+        if self.eat(token) {
+            return Ok(());
+        }
+        match self.peek() {
+            Some(c) => Err(self.error(format!("expected `{}`, found `{}`", token, c))),
+            None => Err(self.error(format!("expected `{}`, found end of formula", token))),
+        }
+    }
+
+    fn implies(&mut self) -> Result<Formula, CtlError> {
+        let left = self.or()?;
+        match self.eat("->") {
+            true => Ok(Formula::Implies(Box::new(left), Box::new(self.implies()?))),
+            false => Ok(left),
+        }
+    }
+
+    fn or(&mut self) -> Result<Formula, CtlError> {
+        let mut left = self.and()?;
+        while self.eat("|") {
+            left = Formula::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Formula, CtlError> {
+        let mut left = self.unary()?;
+        while self.eat("&") {
+            left = Formula::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Formula, CtlError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('!') => {
+                self.pos += 1;
+                Ok(Formula::Not(Box::new(self.unary()?)))
+            }
+            Some('(') => {
+                self.pos += 1;
+                let formula = self.implies()?;
+                self.expect(")")?;
+                Ok(formula)
+            }
+            Some('"') => self.quoted().map(Formula::Prop),
+            Some(c) if is_word_char(c) => self.word_formula(),
+            Some(c) => Err(self.error(format!("unexpected `{}`", c))),
+            None => Err(self.error("expected a formula, found end of formula")),
+        }
+    }
+
+    /// Reads a proposition, a constant or a temporal operator with its
+    /// operands.
+    fn word_formula(&mut self) -> Result<Formula, CtlError> {
+        let start = self.pos;
+        while self.peek().is_some_and(is_word_char) {
+            self.pos += 1;
+        }
+        let word = self.chars[start..self.pos].iter().collect::<String>();
+        let unary =
+            |op: fn(Box<Formula>) -> Formula, parser: &mut Self| Ok(op(Box::new(parser.unary()?)));
+        match word.as_str() {
+            "true" => Ok(Formula::True),
+            "false" => Ok(Formula::False),
+            "EX" => unary(Formula::EX, self),
+            "AX" => unary(Formula::AX, self),
+            "EF" => unary(Formula::EF, self),
+            "AF" => unary(Formula::AF, self),
+            "EG" => unary(Formula::EG, self),
+            "AG" => unary(Formula::AG, self),
+            "E" | "A" => {
+                self.expect("[")?;
+                let left = self.implies()?;
+                self.skip_whitespace();
+                let until = self.pos;
+                if !self.eat("U") || self.peek().is_some_and(is_word_char) {
+                    self.pos = until;
+                    return Err(self.error("expected `U`"));
+                }
+                let right = self.implies()?;
+                self.expect("]")?;
+                match word.as_str() {
+                    "E" => Ok(Formula::EU(Box::new(left), Box::new(right))),
+                    _ => Ok(Formula::AU(Box::new(left), Box::new(right))),
+                }
+            }
+            "U" => {
+                self.pos = start;
+                Err(self.error("`U` outside of `E[..]` or `A[..]`"))
+            }
+            _ => Ok(Formula::Prop(word)),
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String, CtlError> {
+        self.pos += 1;
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c != '"') {
+            self.pos += 1;
+        }
+        let name = self.chars[start..self.pos].iter().collect();
+        self.expect("\"")?;
+        Ok(name)
+    }
+}
+
+/// Computes the states of `graph` that satisfy `formula` by labelling them
+/// with its subformulas from the inside out.
+///
+/// States without outgoing transitions are treated as if they looped on
+/// themselves, so that every path goes on forever.
+pub fn check(graph: &Graph<State, Transition, Directed>, formula: &Formula) -> Vec<NodeIndex> {
+    let model = Model::new(graph);
+    let sat = model.label(formula);
+    graph
+        .node_indices()
+        .filter(|idx| sat[idx.index()])
+        .collect()
+}
+
+/// The transition relation of a graph, indexed by node index.
+struct Model<'a> {
+    graph: &'a Graph<State, Transition, Directed>,
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl<'a> Model<'a> {
+    fn new(graph: &'a Graph<State, Transition, Directed>) -> Self {
+        let bound = graph.node_bound();
+        let mut successors = vec![vec![]; bound];
+        let mut predecessors = vec![vec![]; bound];
+        for edge in graph.edge_references() {
+            let (source, target) = (edge.source().index(), edge.target().index());
+            successors[source].push(target);
+            predecessors[target].push(source);
+        }
+        for idx in graph.node_indices() {
+            let i = idx.index();
+            if successors[i].is_empty() {
+                successors[i].push(i);
+                predecessors[i].push(i);
+            }
+        }
+        Self {
+            graph,
+            successors,
+            predecessors,
+        }
+    }
+
+    fn label(&self, formula: &Formula) -> Vec<bool> {
+        let bound = self.successors.len();
+        match formula {
+            Formula::True => vec![true; bound],
+            Formula::False => vec![false; bound],
+            Formula::Prop(name) => (0..bound)
+                .map(|i| {
+                    self.graph
+                        .node_weight(NodeIndex::new(i))
+                        .and_then(|n| n.data())
+                        .is_some_and(|state| state.props.contains(name))
+                })
+                .collect(),
+            Formula::Not(a) => self.label(a).into_iter().map(|s| !s).collect(),
+            Formula::And(a, b) => zip(self.label(a), self.label(b), |a, b| a && b),
+            Formula::Or(a, b) => zip(self.label(a), self.label(b), |a, b| a || b),
+            Formula::Implies(a, b) => zip(self.label(a), self.label(b), |a, b| !a || b),
+            Formula::EX(a) => self.ex(&self.label(a)),
+            Formula::AX(a) => {
+                let sat = self.label(a);
+                (0..bound)
+                    .map(|i| self.successors[i].iter().all(|j| sat[*j]))
+                    .collect()
+            }
+            Formula::EF(a) => self.eu(&vec![true; bound], &self.label(a)),
+            Formula::AF(a) => self.au(&vec![true; bound], &self.label(a)),
+            Formula::EG(a) => self.eg(self.label(a)),
+            // AG a = !EF !a
+            Formula::AG(a) => {
+                let not_a = self.label(a).into_iter().map(|s| !s).collect::<Vec<_>>();
+                self.eu(&vec![true; bound], &not_a)
+                    .into_iter()
+                    .map(|s| !s)
+                    .collect()
+            }
+            Formula::EU(a, b) => self.eu(&self.label(a), &self.label(b)),
+            Formula::AU(a, b) => self.au(&self.label(a), &self.label(b)),
+        }
+    }
+
+    fn ex(&self, sat: &[bool]) -> Vec<bool> {
+        (0..sat.len())
+            .map(|i| self.successors[i].iter().any(|j| sat[*j]))
+            .collect()
+    }
+
+    /// Least fixpoint: the states satisfying `b`, and the states satisfying
+    /// `a` with a successor in the set.
+    fn eu(&self, a: &[bool], b: &[bool]) -> Vec<bool> {
+        let mut sat = b.to_vec();
+        let mut queue = (0..sat.len()).filter(|i| sat[*i]).collect::<VecDeque<_>>();
+        while let Some(i) = queue.pop_front() {
+            for p in &self.predecessors[i] {
+                if a[*p] && !sat[*p] {
+                    sat[*p] = true;
+                    queue.push_back(*p);
+                }
+            }
+        }
+        sat
+    }
+
+    /// Least fixpoint: the states satisfying `b`, and the states satisfying
+    /// `a` whose successors are all in the set.
+    fn au(&self, a: &[bool], b: &[bool]) -> Vec<bool> {
+        let mut sat = b.to_vec();
+        let mut remaining = self.successors.iter().map(Vec::len).collect::<Vec<_>>();
+        let mut queue = (0..sat.len()).filter(|i| sat[*i]).collect::<VecDeque<_>>();
+        while let Some(i) = queue.pop_front() {
+            for p in &self.predecessors[i] {
+                remaining[*p] -= 1;
+                if remaining[*p] == 0 && a[*p] && !sat[*p] {
+                    sat[*p] = true;
+                    queue.push_back(*p);
+                }
+            }
+        }
+        sat
+    }
+
+    /// Greatest fixpoint: removes the states of `sat` without a successor in
+    /// it until none is left.
+    fn eg(&self, mut sat: Vec<bool>) -> Vec<bool> {
+        let mut remaining = (0..sat.len())
+            .map(|i| self.successors[i].iter().filter(|j| sat[**j]).count())
+            .collect::<Vec<_>>();
+        let mut queue = (0..sat.len())
+            .filter(|i| sat[*i] && remaining[*i] == 0)
+            .collect::<VecDeque<_>>();
+        queue.iter().for_each(|i| sat[*i] = false);
+        while let Some(i) = queue.pop_front() {
+            for p in &self.predecessors[i] {
+                if sat[*p] {
+                    remaining[*p] -= 1;
+                    if remaining[*p] == 0 {
+                        sat[*p] = false;
+                        queue.push_back(*p);
+                    }
+                }
+            }
+        }
+        sat
+    }
+}
+
+fn zip(a: Vec<bool>, b: Vec<bool>, op: impl Fn(bool, bool) -> bool) -> Vec<bool> {
+    a.into_iter().zip(b).map(|(a, b)| op(a, b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Vec2;
+    use egui_graphs::{Edge, Node};
+
+    use super::*;
+
+    /// A graph whose states have the given propositions.
+    fn graph(props: &[&[&str]], edges: &[(usize, usize)]) -> Graph<State, Transition, Directed> {
+        let mut graph = Graph::default();
+        for (i, props) in props.iter().enumerate() {
+            let props = props.iter().map(|p| p.to_string()).collect();
+            graph.add_node(Node::new(
+                Vec2::ZERO,
+                State::new(i, String::new()).with_props(props),
+            ));
+        }
+        for (from, to) in edges {
+            let transition = Transition::new(*from, *to);
+            graph.add_edge(
+                NodeIndex::new(*from),
+                NodeIndex::new(*to),
+                Edge::new(transition),
+            );
+        }
+        graph
+    }
+
+    fn holds(graph: &Graph<State, Transition, Directed>, formula: &str) -> Vec<usize> {
+        let formula = parse(formula).unwrap();
+        check(graph, &formula)
+            .into_iter()
+            .map(|idx| idx.index())
+            .collect()
+    }
+
+    fn prop(name: &str) -> Box<Formula> {
+        Box::new(Formula::Prop(name.to_string()))
+    }
+
+    #[test]
+    fn until_and_globally_on_a_cycle() {
+        // 0 and 1 loop through each other, and 1 may leave to 2
+        let graph = graph(&[&["p"], &["p"], &["q"]], &[(0, 1), (1, 0), (1, 2)]);
+        assert_eq!(holds(&graph, "E[p U q]"), [0, 1, 2]);
+        // the cycle never reaches q
+        assert_eq!(holds(&graph, "A[p U q]"), [2]);
+        assert_eq!(holds(&graph, "AF q"), [2]);
+        assert_eq!(holds(&graph, "EF q"), [0, 1, 2]);
+        assert_eq!(holds(&graph, "EG p"), [0, 1]);
+        assert_eq!(holds(&graph, "AG p"), Vec::<usize>::new());
+        assert_eq!(holds(&graph, "AG EF q"), [0, 1, 2]);
+    }
+
+    #[test]
+    fn parallel_edges() {
+        // two transitions from 0 to 1 and one to 2
+        let both = graph(&[&[], &["q"], &["q"]], &[(0, 1), (0, 1), (0, 2)]);
+        assert_eq!(holds(&both, "AF q"), [0, 1, 2]);
+        assert_eq!(holds(&both, "A[!q U q]"), [0, 1, 2]);
+        assert_eq!(holds(&both, "AX q"), [0, 1, 2]);
+        let one = graph(&[&[], &["q"], &[]], &[(0, 1), (0, 1), (0, 2)]);
+        assert_eq!(holds(&one, "AF q"), [1]);
+        assert_eq!(holds(&one, "EF q"), [0, 1]);
+
+        // 0 only reaches 1 twice over, which leaves p
+        let leaving = graph(&[&["p"], &[]], &[(0, 1), (0, 1)]);
+        assert_eq!(holds(&leaving, "EG p"), Vec::<usize>::new());
+        let staying = graph(&[&["p"], &["p"]], &[(0, 1), (0, 1), (1, 0)]);
+        assert_eq!(holds(&staying, "EG p"), [0, 1]);
+    }
+
+    #[test]
+    fn deadlocks_loop_on_themselves() {
+        let graph = graph(&[&["p"], &["p"]], &[(0, 1)]);
+        assert_eq!(holds(&graph, "AX false"), Vec::<usize>::new());
+        assert_eq!(holds(&graph, "EX true"), [0, 1]);
+        assert_eq!(holds(&graph, "EG p"), [0, 1]);
+        assert_eq!(holds(&graph, "AG p"), [0, 1]);
+        assert_eq!(holds(&graph, "AX p"), [0, 1]);
+    }
+
+    #[test]
+    fn precedence() {
+        let formula = parse("a | b & !c -> d -> e").unwrap();
+        let expected = Formula::Implies(
+            Box::new(Formula::Or(
+                prop("a"),
+                Box::new(Formula::And(prop("b"), Box::new(Formula::Not(prop("c"))))),
+            )),
+            Box::new(Formula::Implies(prop("d"), prop("e"))),
+        );
+        assert_eq!(formula, expected);
+        assert_eq!(
+            parse("EX a & b").unwrap(),
+            Formula::And(Box::new(Formula::EX(prop("a"))), prop("b"))
+        );
+    }
+
+    #[test]
+    fn display_reads_back() {
+        for text in [
+            "AG (req -> AF ack)",
+            "E[!err U done] | A[a U (b & c)]",
+            "a -> (b -> c)",
+            "(a -> b) -> c",
+            "!EX \"x = 1\" & \"EG\"",
+            "EF true & AG !false",
+        ] {
+            let formula = parse(text).unwrap();
+            assert_eq!(parse(&formula.to_string()).unwrap(), formula, "{}", text);
+        }
+        assert_eq!(parse("\"EG\"").unwrap().to_string(), "\"EG\"");
+    }
+
+    #[test]
+    fn error_columns() {
+        let error = |text| parse(text).unwrap_err();
+        assert_eq!(
+            error("a &"),
+            CtlError {
+                column: 4,
+                message: "expected a formula, found end of formula".to_string()
+            }
+        );
+        assert_eq!(error("E[a b]").to_string(), "column 5: expected `U`");
+        assert_eq!(error("(a").column, 3);
+        assert_eq!(error("a )").message, "unexpected `)`");
+        assert_eq!(error("U").message, "`U` outside of `E[..]` or `A[..]`");
+        assert_eq!(error("\"a").column, 3);
+    }
+}
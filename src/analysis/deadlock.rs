@@ -0,0 +1,89 @@
+use egui_graphs::Graph;
+use petgraph::{stable_graph::NodeIndex, Directed, Direction};
+
+use crate::graph::{state::State, transition::Transition};
+
+use super::path::{Path, ShortestPaths};
+
+/// A state without outgoing transitions that is not accepting.
+#[derive(Debug, Clone)]
+pub struct Deadlock {
+    pub state: NodeIndex,
+    /// Shortest path from the initial states, if it can be reached.
+    pub path: Option<Path>,
+}
+
+/// Finds the deadlocks of `graph` and the shortest path to each of them.
+pub fn deadlocks(graph: &Graph<State, Transition, Directed>) -> Vec<Deadlock> {
+    let initial = graph
+        .node_indices()
+        .filter(|idx| graph[*idx].data().is_some_and(|state| state.initial));
+    let paths = ShortestPaths::from(graph, initial);
+    graph
+        .node_indices()
+        .filter(|idx| {
+            graph[*idx].data().is_some_and(|state| !state.accepting)
+                && graph
+                    .neighbors_directed(*idx, Direction::Outgoing)
+                    .next()
+                    .is_none()
+        })
+        .map(|state| Deadlock {
+            state,
+            path: paths.to(graph, state),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::graph::StateSpace;
+
+    #[test]
+    fn accepting_sinks_are_no_deadlocks() {
+        // 1 is an accepting sink, 2 a plain sink, 3 an unreachable sink and 4
+        // loops on itself
+        let space = StateSpace::new(
+            (0..5)
+                .map(|index| State::new(index, String::new()))
+                .collect(),
+            [(0, 1), (0, 2), (0, 4), (4, 4)]
+                .into_iter()
+                .map(|(from, to)| Transition::new(from, to))
+                .collect(),
+        )
+        .with_initial([0])
+        .with_accepting([1]);
+        let graph = Graph::try_from(&space).unwrap();
+        let index = |idx: NodeIndex| graph[idx].data().unwrap().index;
+
+        let found = deadlocks(&graph);
+        let states = found
+            .iter()
+            .map(|deadlock| index(deadlock.state))
+            .collect::<HashSet<_>>();
+        assert_eq!(states, [2, 3].into());
+        for deadlock in &found {
+            let path = deadlock
+                .path
+                .as_ref()
+                .map(|path| path.nodes.iter().map(|idx| index(*idx)).collect::<Vec<_>>());
+            match index(deadlock.state) {
+                2 => assert_eq!(path, Some(vec![0, 2])),
+                _ => assert_eq!(path, None),
+            }
+        }
+
+        // the graph marks the same states
+        let marked = graph
+            .node_weights()
+            .filter_map(|node| node.data())
+            .filter(|state| state.deadlock)
+            .map(|state| state.index)
+            .collect::<HashSet<_>>();
+        assert_eq!(marked, states);
+    }
+}
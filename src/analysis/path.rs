@@ -0,0 +1,146 @@
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use egui_graphs::Graph;
+use petgraph::{
+    stable_graph::{EdgeIndex, NodeIndex},
+    visit::EdgeRef,
+    Directed,
+};
+
+/// States and transitions along a path through the state space.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Path {
+    pub nodes: Vec<NodeIndex>,
+    /// `edges[i]` leads from `nodes[i]` to `nodes[i + 1]`.
+    pub edges: Vec<EdgeIndex>,
+}
+
+/// Paths with the fewest transitions from a set of sources to every state
+/// reachable from them.
+pub struct ShortestPaths {
+    /// The transition each reached state is first reached by, `None` for the
+    /// sources.
+    reached: HashMap<NodeIndex, Option<EdgeIndex>>,
+}
+
+impl ShortestPaths {
+    /// Searches `graph` breadth first from `sources`.
+    pub fn from<N: Clone, E: Clone>(
+        graph: &Graph<N, E, Directed>,
+        sources: impl IntoIterator<Item = NodeIndex>,
+    ) -> Self {
+        let mut reached = HashMap::new();
+        let mut queue = VecDeque::new();
+        for source in sources {
+            if reached.insert(source, None).is_none() {
+                queue.push_back(source);
+            }
+        }
+        while let Some(idx) = queue.pop_front() {
+            for edge in graph.edges(idx) {
+                if let Entry::Vacant(entry) = reached.entry(edge.target()) {
+                    entry.insert(Some(edge.id()));
+                    queue.push_back(edge.target());
+                }
+            }
+        }
+        Self { reached }
+    }
+
+    /// The path to `target`, if it can be reached.
+    pub fn to<N: Clone, E: Clone>(
+        &self,
+        graph: &Graph<N, E, Directed>,
+        target: NodeIndex,
+    ) -> Option<Path> {
+        let mut path = Path {
+            nodes: vec![target],
+            edges: vec![],
+        };
+        let mut idx = target;
+        while let Some(edge) = self.reached.get(&idx)? {
+            let (source, _) = graph.edge_endpoints(*edge)?;
+            path.nodes.push(source);
+            path.edges.push(*edge);
+            idx = source;
+        }
+        path.nodes.reverse();
+        path.edges.reverse();
+        Some(path)
+    }
+}
+
+/// A path with the fewest transitions from any of `sources` to `target`.
+pub fn shortest_path<N: Clone, E: Clone>(
+    graph: &Graph<N, E, Directed>,
+    sources: impl IntoIterator<Item = NodeIndex>,
+    target: NodeIndex,
+) -> Option<Path> {
+    ShortestPaths::from(graph, sources).to(graph, target)
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Vec2;
+    use egui_graphs::{Edge, Node};
+
+    use super::*;
+
+    fn graph(nodes: usize, edges: &[(usize, usize)]) -> Graph<(), (), Directed> {
+        let mut graph = Graph::default();
+        let indices = (0..nodes)
+            .map(|_| graph.add_node(Node::new(Vec2::ZERO, ())))
+            .collect::<Vec<_>>();
+        for (from, to) in edges {
+            graph.add_edge(indices[*from], indices[*to], Edge::new(()));
+        }
+        graph
+    }
+
+    fn n(index: usize) -> NodeIndex {
+        NodeIndex::new(index)
+    }
+
+    /// Checks that the edges of `path` lead from one of its nodes to the next.
+    fn assert_connected(graph: &Graph<(), (), Directed>, path: &Path) {
+        assert_eq!(path.edges.len() + 1, path.nodes.len());
+        for (i, edge) in path.edges.iter().enumerate() {
+            assert_eq!(
+                graph.edge_endpoints(*edge),
+                Some((path.nodes[i], path.nodes[i + 1]))
+            );
+        }
+    }
+
+    #[test]
+    fn unreachable_target() {
+        let graph = graph(3, &[(0, 1), (2, 0)]);
+        assert_eq!(shortest_path(&graph, [n(0)], n(2)), None);
+        assert_eq!(shortest_path(&graph, [], n(1)), None);
+    }
+
+    #[test]
+    fn from_the_nearest_initial_state() {
+        let graph = graph(5, &[(0, 1), (1, 2), (2, 3), (4, 3)]);
+        let path = shortest_path(&graph, [n(0), n(4)], n(3)).unwrap();
+        assert_eq!(path.nodes, [n(4), n(3)]);
+        assert_connected(&graph, &path);
+
+        let path = shortest_path(&graph, [n(0), n(4)], n(2)).unwrap();
+        assert_eq!(path.nodes, [n(0), n(1), n(2)]);
+        assert_connected(&graph, &path);
+    }
+
+    #[test]
+    fn parallel_edges_and_loops() {
+        let graph = graph(3, &[(0, 0), (0, 1), (0, 1), (1, 1), (1, 2)]);
+        let path = shortest_path(&graph, [n(0)], n(2)).unwrap();
+        assert_eq!(path.nodes, [n(0), n(1), n(2)]);
+        assert_connected(&graph, &path);
+
+        // a source is reached without taking its loop
+        let path = shortest_path(&graph, [n(0)], n(0)).unwrap();
+        assert_eq!(path.nodes, [n(0)]);
+        assert!(path.edges.is_empty());
+    }
+}
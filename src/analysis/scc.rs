@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use egui_graphs::Graph;
+use petgraph::{algo::kosaraju_scc, stable_graph::NodeIndex, Directed};
+
+/// The strongly connected components of a state space.
+#[derive(Debug, Clone, Default)]
+pub struct Components {
+    /// States of each component.
+    pub members: Vec<Vec<NodeIndex>>,
+    /// Component of every state.
+    pub component: HashMap<NodeIndex, usize>,
+    /// Whether no transition leaves the component.
+    pub bottom: Vec<bool>,
+    /// Whether the component contains a cycle, i.e. has more than one state
+    /// or a state with a loop.
+    pub cyclic: Vec<bool>,
+}
+
+impl Components {
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+/// Computes the strongly connected components of `graph` with Kosaraju's
+/// algorithm, which unlike Tarjan's does not recurse along long paths.
+pub fn components<N: Clone, E: Clone>(graph: &Graph<N, E, Directed>) -> Components {
+    let members = kosaraju_scc(graph);
+    let component = members
+        .iter()
+        .enumerate()
+        .flat_map(|(i, states)| states.iter().map(move |idx| (*idx, i)))
+        .collect::<HashMap<_, _>>();
+
+    let mut bottom = vec![true; members.len()];
+    let mut cyclic = members
+        .iter()
+        .map(|states| states.len() > 1)
+        .collect::<Vec<_>>();
+    graph.edge_indices().for_each(|idx| {
+        let (source, target) = graph.edge_endpoints(idx).unwrap();
+        let (from, to) = (component[&source], component[&target]);
+        if from != to {
+            bottom[from] = false;
+        } else if source == target {
+            cyclic[from] = true;
+        }
+    });
+
+    Components {
+        members,
+        component,
+        bottom,
+        cyclic,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Vec2;
+    use egui_graphs::{Edge, Node};
+
+    use super::*;
+
+    fn graph(states: usize, edges: &[(usize, usize)]) -> Graph<(), (), Directed> {
+        let mut graph = Graph::default();
+        for _ in 0..states {
+            graph.add_node(Node::new(Vec2::ZERO, ()));
+        }
+        for (from, to) in edges {
+            graph.add_edge(NodeIndex::new(*from), NodeIndex::new(*to), Edge::new(()));
+        }
+        graph
+    }
+
+    /// Whether the component of `state` is bottom and cyclic.
+    fn kind(components: &Components, state: usize) -> (bool, bool) {
+        let i = components.component[&NodeIndex::new(state)];
+        (components.bottom[i], components.cyclic[i])
+    }
+
+    #[test]
+    fn bottom_and_cyclic_components() {
+        // 0 -> {1, 2} -> 3 with a loop at 4, which is reached from 0 as well
+        let components = components(&graph(5, &[(0, 1), (1, 2), (2, 1), (2, 3), (0, 4), (4, 4)]));
+        assert_eq!(components.len(), 4);
+        assert_eq!(
+            components.component[&NodeIndex::new(1)],
+            components.component[&NodeIndex::new(2)]
+        );
+        assert_eq!(kind(&components, 0), (false, false));
+        assert_eq!(kind(&components, 1), (false, true));
+        // a sink is a bottom component without a cycle
+        assert_eq!(kind(&components, 3), (true, false));
+        // a state with a loop is a cyclic singleton
+        assert_eq!(kind(&components, 4), (true, true));
+    }
+
+    #[test]
+    fn long_chain() {
+        let n = 100_000;
+        let edges = (1..n).map(|i| (i - 1, i)).collect::<Vec<_>>();
+        let components = components(&graph(n, &edges));
+        assert_eq!(components.len(), n);
+        assert_eq!(components.bottom.iter().filter(|b| **b).count(), 1);
+        assert_eq!(kind(&components, n - 1), (true, false));
+    }
+}
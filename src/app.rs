@@ -1,41 +1,120 @@
-use std::path::PathBuf;
+use std::{collections::HashSet, io::Read, path::PathBuf};
 
 use crossbeam::channel::{unbounded, Receiver, Sender};
-use egui::{Layout, ScrollArea, Vec2, Visuals};
+use egui::{Layout, Rect, ScrollArea, Vec2, Visuals};
 use egui_graphs::{
-    Change, Graph, GraphView, Node, SettingsInteraction, SettingsNavigation, SettingsStyle,
+    Change, ChangeNode, Graph, GraphView, Node, SettingsInteraction, SettingsNavigation,
+    SettingsStyle,
 };
 use fdg_sim::{
     force::{Force, Value},
     glam::Vec3,
     Simulation,
 };
-use petgraph::Directed;
+use petgraph::{stable_graph::NodeIndex, visit::EdgeRef, Directed};
 
 use crate::{
+    analysis::scc::components,
+    export::{
+        dot::to_dot,
+        graphml::to_graphml,
+        json::to_json,
+        svg::{to_svg, SvgView},
+    },
     graph::{
         construct_simulation,
         state::State,
         transition::Transition,
-        translator::{SLIMTranslator, Translator},
+        translator::registry::{self, Format, Reader, FORMATS, PROBE_LEN},
+        StateSpace,
     },
+    live::{LiveGraph, LiveMessage, LiveSource, LiveStream},
+    loader::{LoadMessage, LoadTask, LoadedGraph},
+    reload::{self, FileWatcher, ReloadDiff},
+    session::{self, Session},
     settings::{self},
-    views::about::AboutWindow,
+    views::{
+        about::AboutWindow,
+        components::ComponentsPanel,
+        condensation::CondensationView,
+        ctl::CtlPanel,
+        deadlock::DeadlockPanel,
+        error::ErrorWindow,
+        format::FormatWindow,
+        hypergraph::HypergraphWindow,
+        live::LiveWindow,
+        lmntal::process_tree,
+        overlay::{
+            draw_edge_labels, draw_reload_diff, Overlay, ViewTransform, COLOR_BOTTOM,
+            COLOR_SATISFYING,
+        },
+        path::PathPanel,
+        progress::ProgressWindow,
+        repair::RepairWindow,
+    },
 };
 
 const SIMULATION_DT: f32 = 0.035;
+/// Most events of a live stream added to the graph in one frame.
+const LIVE_EVENTS_PER_FRAME: usize = 1000;
+/// Seconds the states that changed with a reload stay highlighted.
+const RELOAD_HIGHLIGHT: f64 = 2.;
+
+/// Where the state space that is being loaded comes from.
+enum Origin {
+    /// A file read as the format with the given name.
+    File(PathBuf, &'static str),
+    /// The opened file again after it changed.
+    Reload(PathBuf, &'static str),
+    Stdin,
+    /// The repaired state space of the previous load.
+    Repair,
+}
 
 pub struct MainApp {
     file: Option<PathBuf>,
+    /// Name of the format `file` was read as.
+    file_format: Option<&'static str>,
+    loading: Origin,
+    /// Session to restore once its source is loaded.
+    pending_session: Option<Session>,
+    /// Whether to reload `file` when it changes.
+    watch_file: bool,
+    watcher: Option<FileWatcher>,
+    /// Changes of the last reload and the time they were shown first.
+    reload_diff: Option<(ReloadDiff, f64)>,
+    live: Option<LiveStream>,
+    live_graph: LiveGraph,
 
     graph: Graph<State, Transition, Directed>,
     sim: Simulation<State, f32>,
     force: Force<State, f32>,
     loaded: bool,
+    /// Whether the layout is frozen, e.g. to keep the positions of a
+    /// restored session.
+    layout_paused: bool,
     // layout: layout::Layout,
-    selected_nodes: Vec<Node<State>>,
+    selected_nodes: Vec<NodeIndex>,
+    /// Area the graph was last drawn in.
+    view_rect: Rect,
+    /// Zoom and pan the graph was last drawn with.
+    view_transform: ViewTransform,
+    path: PathPanel,
+    components: ComponentsPanel,
+    deadlocks: DeadlockPanel,
+    ctl: CtlPanel,
+    /// The graph with its components condensed, shown instead of it.
+    condensation: Option<CondensationView>,
+    /// Name of the atoms to select the LMNtal states with.
+    atom_filter: String,
 
     about: Option<AboutWindow>,
+    error: Option<ErrorWindow>,
+    repair: Option<RepairWindow>,
+    format: Option<FormatWindow>,
+    progress: Option<ProgressWindow>,
+    listen: Option<LiveWindow>,
+    hypergraphs: Vec<HypergraphWindow>,
     settings_interaction: settings::SettingsInteraction,
     settings_navigation: settings::SettingsNavigation,
     settings_style: settings::SettingsStyle,
@@ -44,38 +123,543 @@ pub struct MainApp {
     changes_sender: Sender<Change>,
 
     dark_mode: bool,
+
+    /// Handle for background loaders to request repaints.
+    ctx: egui::Context,
 }
 
 impl MainApp {
     /// Called once before the first frame.
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         let (changes_sender, changes_receiver) = unbounded();
         Self {
             file: None,
+            file_format: None,
+            loading: Origin::Repair,
+            pending_session: None,
+            watch_file: false,
+            watcher: None,
+            reload_diff: None,
+            live: None,
+            live_graph: LiveGraph::default(),
             graph: Graph::new(),
             sim: construct_simulation(&Graph::new()),
             loaded: false,
+            layout_paused: false,
             // layout: layout::Layout::Radial,
             force: fdg_sim::force::fruchterman_reingold_weighted(100., 0.95),
             selected_nodes: vec![],
+            view_rect: Rect::NOTHING,
+            view_transform: ViewTransform::default(),
+            path: PathPanel::default(),
+            components: ComponentsPanel::default(),
+            deadlocks: DeadlockPanel::default(),
+            ctl: CtlPanel::default(),
+            condensation: None,
+            atom_filter: String::new(),
             about: None,
+            error: None,
+            repair: None,
+            format: None,
+            progress: None,
+            listen: None,
+            hypergraphs: vec![],
             settings_interaction: settings::SettingsInteraction::default(),
             settings_navigation: settings::SettingsNavigation::default(),
             settings_style: settings::SettingsStyle::default(),
             changes_receiver,
             changes_sender,
             dark_mode: false,
+            ctx: cc.egui_ctx.clone(),
         }
     }
 
-    pub fn handle_changes(&self) {
-        self.changes_receiver
-            .try_iter()
-            .for_each(|change| match change {
-                Change::Node(_) => {}
-                Change::Edge(_) => {}
-                Change::SubGraph(_) => {}
-            });
+    /// Detects the format of `path` and loads it in the background, asking
+    /// the user if the format is ambiguous.
+    fn open(&mut self, path: PathBuf) {
+        let mut head = vec![];
+        let read = std::fs::File::open(&path)
+            .and_then(|file| file.take(PROBE_LEN as u64).read_to_end(&mut head));
+        if let Err(e) = read {
+            self.error = Some(ErrorWindow::new(
+                "Failed to open file",
+                format!("{}: {}", path.display(), e),
+            ));
+            return;
+        }
+
+        match registry::detect(Some(&path), &String::from_utf8_lossy(&head))[..] {
+            [format] => self.open_as(format, path),
+            ref candidates => self.format = Some(FormatWindow::new(path, candidates.to_vec())),
+        }
+    }
+
+    /// Loads `path` in the background, reading it as `format`.
+    fn open_as(&mut self, format: &'static Format, path: PathBuf) {
+        self.cancel_loading();
+        self.live = None;
+        self.pending_session = None;
+        self.loading = Origin::File(path.clone(), format.name);
+        let ctx = self.ctx.clone();
+        let task = match format.reader {
+            Reader::Text(translate) => LoadTask::open(
+                path,
+                Box::new(move |content| translate(content).map_err(|e| e.to_string())),
+                ctx,
+            ),
+            Reader::Stream(translate) => LoadTask::open_stream(
+                path,
+                Box::new(move |reader| translate(reader).map_err(|e| e.to_string())),
+                ctx,
+            ),
+            Reader::WithSiblings(translate) => {
+                let siblings = path.clone();
+                LoadTask::open(
+                    path,
+                    Box::new(move |content| {
+                        translate(content, Some(&siblings)).map_err(|e| e.to_string())
+                    }),
+                    ctx,
+                )
+            }
+        };
+        self.progress = Some(ProgressWindow::new(task));
+    }
+
+    /// Opens the command line argument `input`, a file or `-` for the
+    /// standard input.
+    pub fn open_input(&mut self, input: &str) {
+        match input {
+            "-" => {
+                self.cancel_loading();
+                self.live = None;
+                self.pending_session = None;
+                self.loading = Origin::Stdin;
+                let task = LoadTask::stdin(
+                    Box::new(|reader| {
+                        registry::translate_detected(reader).map_err(|e| e.to_string())
+                    }),
+                    self.ctx.clone(),
+                );
+                self.progress = Some(ProgressWindow::new(task));
+            }
+            path => self.open(PathBuf::from(path)),
+        }
+    }
+
+    /// Listens for a model checker on `source` and shows the states it sends
+    /// while they arrive.
+    fn listen(&mut self, source: LiveSource) {
+        self.cancel_loading();
+        self.pending_session = None;
+        self.live = None;
+        match LiveStream::start(source, self.ctx.clone()) {
+            Ok(live) => {
+                self.live = Some(live);
+                self.clear_live();
+            }
+            Err(e) => self.error = Some(ErrorWindow::new("Failed to listen", e)),
+        }
+    }
+
+    /// Listens on the command line argument `source`, see [`LiveSource`].
+    pub fn listen_input(&mut self, source: &str) {
+        match source.parse() {
+            Ok(source) => self.listen(source),
+            Err(e) => self.error = Some(ErrorWindow::new("Failed to listen", e)),
+        }
+    }
+
+    /// Starts over with an empty graph for a new exploration.
+    fn clear_live(&mut self) {
+        self.graph = Graph::new();
+        self.sim = construct_simulation(&self.graph);
+        self.live_graph = LiveGraph::default();
+        self.layout_paused = false;
+        self.selected_nodes.clear();
+        self.path = PathPanel::default();
+        self.deadlocks = DeadlockPanel::default();
+        self.ctl = CtlPanel::default();
+        self.components.components = None;
+        self.condensation = None;
+        self.hypergraphs.clear();
+        self.reload_diff = None;
+        self.file = None;
+        self.file_format = None;
+        self.loaded = true;
+    }
+
+    /// Adds the events that arrived from the model checker to the graph.
+    fn update_live(&mut self) {
+        let mut changed = false;
+        let mut finished = false;
+        for _ in 0..LIVE_EVENTS_PER_FRAME {
+            let Some(message) = self.live.as_mut().and_then(|live| live.poll()) else {
+                break;
+            };
+            match message {
+                LiveMessage::Connected(_) => self.clear_live(),
+                LiveMessage::Event(event) => {
+                    self.live_graph.apply(event, &mut self.graph, &mut self.sim);
+                    changed = true;
+                }
+                LiveMessage::Done | LiveMessage::Disconnected => {
+                    self.live_graph.finish(&mut self.graph, &mut self.sim);
+                    changed = true;
+                    finished = true;
+                }
+                // counted by the stream and shown in the status bar
+                LiveMessage::Invalid(_) => {}
+                LiveMessage::Failed(message) => {
+                    self.error = Some(ErrorWindow::new("Live stream failed", message));
+                    self.live = None;
+                }
+            }
+        }
+        if changed {
+            // a scan or check does not hold for the grown graph
+            self.deadlocks = DeadlockPanel::default();
+            self.ctl.graph_changed();
+            // the components changed with the graph, the condensed view is
+            // only condensed again once the exploration is complete
+            if let Some(condensation) = &mut self.condensation {
+                self.components.components = None;
+                condensation.mark_stale();
+            }
+            if finished {
+                self.refresh_condensation();
+            }
+            self.color_states();
+        }
+    }
+
+    /// Condenses the changed graph again, keeping what the condensed view
+    /// shows.
+    fn refresh_condensation(&mut self) {
+        let Some(condensation) = &mut self.condensation else {
+            return;
+        };
+        let components = components(&self.graph);
+        self.components.components = Some(components.clone());
+        condensation.refresh(&self.graph, components);
+    }
+
+    /// Condenses the graph when the condensed view was turned on and drops
+    /// the condensation when it was turned off.
+    fn update_condensation(&mut self) {
+        let condensed = self.loaded && self.settings_interaction.condensed;
+        match (condensed, &self.condensation) {
+            (true, None) => {
+                let components = self
+                    .components
+                    .components
+                    .get_or_insert_with(|| components(&self.graph))
+                    .clone();
+                self.condensation = Some(CondensationView::new(&self.graph, components));
+            }
+            (false, Some(_)) => self.condensation = None,
+            _ => {}
+        }
+    }
+
+    /// Starts or stops watching the opened file and reloads it when it changed.
+    fn watch(&mut self) {
+        let watched = self.file.as_ref().filter(|_| self.watch_file);
+        match (watched, &self.watcher) {
+            (Some(path), Some(watcher)) if &watcher.path == path => {}
+            (Some(path), _) => {
+                self.watcher = Some(FileWatcher::new(path.clone(), self.ctx.clone()));
+            }
+            (None, _) => self.watcher = None,
+        }
+
+        // changes stay queued while something else is loaded or repaired
+        let busy = self.progress.is_some() || self.repair.is_some();
+        if !busy && self.watcher.as_ref().is_some_and(|w| w.changed()) {
+            self.reload();
+        }
+    }
+
+    /// Loads the opened file again, keeping the layout of unchanged states.
+    fn reload(&mut self) {
+        let (Some(path), Some(format)) =
+            (self.file.clone(), self.file_format.and_then(registry::find))
+        else {
+            return;
+        };
+        self.open_as(format, path.clone());
+        self.loading = Origin::Reload(path, format.name);
+    }
+
+    fn cancel_loading(&mut self) {
+        if let Some(progress) = self.progress.take() {
+            progress.task().cancel();
+        }
+    }
+
+    fn load_state_space(&mut self, state_space: StateSpace) {
+        self.cancel_loading();
+        self.loading = Origin::Repair;
+        let task = LoadTask::build(state_space, self.ctx.clone());
+        self.progress = Some(ProgressWindow::new(task));
+    }
+
+    fn export_dot(&mut self, positions: bool) {
+        let dot = to_dot(&self.graph, &self.settings_style, positions);
+        self.save("Graphviz DOT", "dot", dot);
+    }
+
+    fn export_svg(&mut self) {
+        let visuals = self.ctx.style().visuals.clone();
+        let svg = match &self.condensation {
+            Some(condensation) => condensation.to_svg(&self.settings_style, &visuals),
+            None => to_svg(
+                &self.graph,
+                &SvgView {
+                    rect: self.view_rect,
+                    transform: self.view_transform,
+                    selection_depth: self.settings_interaction.selection_depth,
+                    style: &self.settings_style,
+                    visuals: &visuals,
+                    overlays: self.overlays(),
+                    backdrop: vec![],
+                },
+                Transition::caption,
+            ),
+        };
+        self.save("SVG", "svg", svg);
+    }
+
+    /// Highlights drawn over the graph, from the bottom up.
+    fn overlays(&self) -> Vec<Overlay<'_>> {
+        let mut overlays = vec![
+            Overlay::Rings(self.components.bottom_states(), COLOR_BOTTOM),
+            Overlay::Rings(self.deadlocks.states(), self.settings_style.color_deadlock),
+            Overlay::Rings(self.ctl.states(), COLOR_SATISFYING),
+        ];
+        if let Some(path) = self.path.path.as_ref().or(self.deadlocks.path()) {
+            overlays.push(Overlay::Path(path));
+        }
+        overlays
+    }
+
+    /// Reopens the source of the session at `path` and restores the view
+    /// once it is loaded.
+    fn open_session(&mut self, path: PathBuf) {
+        let session = match Session::load(&path) {
+            Ok(session) => session,
+            Err(e) => {
+                self.error = Some(ErrorWindow::new("Failed to open session", e));
+                return;
+            }
+        };
+        let Some(format) = registry::find(&session.format) else {
+            self.error = Some(ErrorWindow::new(
+                "Failed to open session",
+                format!("{}: unknown format `{}`", path.display(), session.format),
+            ));
+            return;
+        };
+        self.open_as(format, session.source.clone());
+        self.pending_session = Some(session);
+    }
+
+    fn save_session(&mut self) {
+        let (Some(source), Some(format)) = (self.file.clone(), self.file_format) else {
+            self.error = Some(ErrorWindow::new(
+                "Failed to save session",
+                "Only state spaces opened from a file can be saved in a session.",
+            ));
+            return;
+        };
+        let session = Session {
+            source,
+            format: format.to_string(),
+            positions: session::positions(&self.graph),
+            force: session::force_values(&self.force),
+            interaction: self.settings_interaction.clone(),
+            navigation: self.settings_navigation.clone(),
+            style: self.settings_style.clone(),
+            selected: self
+                .selected_nodes
+                .iter()
+                .filter_map(|idx| self.graph[*idx].data().map(|state| state.index))
+                .collect(),
+            dark_mode: self.dark_mode,
+        };
+        let Some(path) = self.save_path("Graphew session", "graphew") else {
+            return;
+        };
+        if let Err(e) = session.save(&path) {
+            self.error = Some(ErrorWindow::new("Failed to save session", e));
+        }
+    }
+
+    /// Applies the settings, layout and selection of `session` to the loaded graph.
+    fn restore_session(&mut self, session: Session) {
+        self.settings_interaction = session.interaction;
+        self.settings_navigation = session.navigation;
+        self.settings_style = session.style;
+        self.dark_mode = session.dark_mode;
+        session::restore_force(&mut self.force, &session.force);
+        session::restore_positions(&mut self.graph, &mut self.sim, &session.positions);
+        // the simulation would move the states away from their positions
+        self.layout_paused = true;
+
+        let selected = session.selected.into_iter().collect::<HashSet<_>>();
+        self.graph.node_weights_mut().for_each(|node| {
+            let selected = node
+                .data()
+                .is_some_and(|state| selected.contains(&state.index));
+            node.set_selected(selected);
+        });
+    }
+
+    /// Asks for a file to write to, named after the opened file.
+    fn save_path(&self, filter: &str, extension: &str) -> Option<PathBuf> {
+        let name = self
+            .file
+            .as_ref()
+            .and_then(|file| file.file_stem())
+            .map(|stem| format!("{}.{}", stem.to_string_lossy(), extension))
+            .unwrap_or_else(|| format!("statespace.{}", extension));
+        rfd::FileDialog::new()
+            .add_filter(filter, &[extension])
+            .set_file_name(&name)
+            .save_file()
+    }
+
+    /// Asks for a file to write `content` to.
+    fn save(&mut self, filter: &str, extension: &str, content: String) {
+        let Some(path) = self.save_path(filter, extension) else {
+            return;
+        };
+        if let Err(e) = std::fs::write(&path, content) {
+            self.error = Some(ErrorWindow::new(
+                "Failed to export",
+                format!("{}: {}", path.display(), e),
+            ));
+        }
+    }
+
+    /// Applies the result of a finished background load.
+    fn finish_loading(&mut self, message: LoadMessage) {
+        match message {
+            LoadMessage::Loaded(loaded) => {
+                let LoadedGraph { graph, sim, report } = *loaded;
+                if !report.is_empty() {
+                    self.error = Some(ErrorWindow::new("Unreachable states", report.to_string()));
+                }
+                let reloaded = matches!(self.loading, Origin::Reload(..));
+                self.take_origin();
+                let previous = std::mem::replace(&mut self.graph, graph);
+                self.sim = sim;
+                self.selected_nodes.clear();
+                self.path = PathPanel::default();
+                self.deadlocks = DeadlockPanel::default();
+                self.ctl = CtlPanel::default();
+                self.components.components = None;
+                self.condensation = None;
+                self.hypergraphs.clear();
+                self.reload_diff = None;
+                if reloaded {
+                    let diff = reload::keep_layout(&previous, &mut self.graph, &mut self.sim);
+                    if !diff.is_empty() {
+                        self.reload_diff = Some((diff, self.ctx.input(|i| i.time)));
+                    }
+                }
+                if !reloaded {
+                    self.layout_paused = false;
+                }
+                if let Some(session) = self.pending_session.take() {
+                    self.restore_session(session);
+                }
+                self.color_states();
+                self.loaded = true;
+            }
+            LoadMessage::Invalid(report, state_space) => {
+                self.take_origin();
+                self.repair = Some(RepairWindow::new(report, *state_space));
+            }
+            LoadMessage::Failed(message) => {
+                self.pending_session = None;
+                self.error = Some(ErrorWindow::new("Failed to open file", message));
+            }
+            LoadMessage::Progress(..) | LoadMessage::BytesRead(_) => {}
+        }
+    }
+
+    /// Remembers where the state space that was just loaded comes from.
+    fn take_origin(&mut self) {
+        match std::mem::replace(&mut self.loading, Origin::Repair) {
+            Origin::File(path, format) | Origin::Reload(path, format) => {
+                self.file = Some(path);
+                self.file_format = Some(format);
+            }
+            Origin::Stdin => {
+                self.file = None;
+                self.file_format = None;
+            }
+            Origin::Repair => {}
+        }
+    }
+
+    /// Selects the LMNtal states that contain an atom named `atom_filter`.
+    fn select_with_atom(&mut self) {
+        let name = self.atom_filter.trim();
+        self.graph.node_weights_mut().for_each(|node| {
+            let selected = node
+                .data()
+                .and_then(|state| state.lmntal.as_ref())
+                .is_some_and(|process| process.has_atom(name));
+            node.set_selected(selected);
+        });
+    }
+
+    /// Colors the states by their strongly connected component, or colors
+    /// initial, accepting and deadlock states.
+    fn color_states(&mut self) {
+        let style = &self.settings_style;
+        let indices = self.graph.node_indices().collect::<Vec<_>>();
+        for idx in indices {
+            let n = &mut self.graph[idx];
+            let color = match (self.components.color_of(idx), n.data()) {
+                (Some(color), _) => Some(color),
+                (None, Some(state)) if state.initial => Some(style.color_initial),
+                (None, Some(state)) if state.accepting => Some(style.color_accepting),
+                (None, Some(state)) if state.deadlock => Some(style.color_deadlock),
+                _ => None,
+            };
+            *n = match color {
+                Some(color) => n.with_color(color),
+                None => without_color(n),
+            };
+        }
+    }
+
+    /// Opens a window with the hypergraph of the LMNtal state at `idx`.
+    fn open_hypergraph(&mut self, idx: NodeIndex) {
+        let Some(state) = self.graph.node_weight(idx).and_then(|n| n.data()) else {
+            return;
+        };
+        let Some(process) = &state.lmntal else {
+            return;
+        };
+        if self.hypergraphs.iter().any(|w| w.state == state.index) {
+            return;
+        }
+        self.hypergraphs
+            .push(HypergraphWindow::new(state.index, process.clone()));
+    }
+
+    pub fn handle_changes(&mut self) {
+        let changes = self.changes_receiver.try_iter().collect::<Vec<_>>();
+        changes.into_iter().for_each(|change| match change {
+            Change::Node(ChangeNode::DoubleClicked { id }) => self.open_hypergraph(id),
+            Change::Node(_) => {}
+            Change::Edge(_) => {}
+            Change::SubGraph(_) => {}
+        });
     }
 
     fn update_simulation(&mut self) {
@@ -125,7 +709,7 @@ impl MainApp {
             g_n.set_location(Vec2::new(loc.x, loc.y));
 
             if g_n.selected() {
-                self.selected_nodes.push(g_n.clone());
+                self.selected_nodes.push(*g_n_idx);
             }
         });
 
@@ -148,16 +732,49 @@ impl MainApp {
                         -10..=10,
                     ));
                     ui.end_row();
+
+                    ui.label("condense");
+                    ui.checkbox(&mut self.settings_interaction.condensed, "")
+                        .on_hover_text("Show each strongly connected component as one node");
+                    ui.end_row();
                 });
 
             ui.separator();
 
+            ui.horizontal(|ui| {
+                ui.label("atom");
+                ui.text_edit_singleline(&mut self.atom_filter);
+                if ui
+                    .button("select")
+                    .on_hover_text("Select the LMNtal states with an atom of this name")
+                    .clicked()
+                {
+                    self.select_with_atom();
+                }
+            });
+
+            ui.separator();
+
             ui.checkbox(&mut self.settings_style.labels_always, "show labels");
+            ui.checkbox(
+                &mut self.settings_style.edge_labels_always,
+                "show transition labels",
+            );
+
+            ui.horizontal(|ui| {
+                let style = &self.settings_style;
+                ui.colored_label(style.color_initial, "⏺ initial");
+                ui.colored_label(style.color_accepting, "⏺ accepting");
+                ui.colored_label(style.color_deadlock, "⏺ deadlock");
+            });
 
             ui.separator();
 
             ui.label("Force settings");
 
+            ui.checkbox(&mut self.layout_paused, "pause layout")
+                .on_hover_text("Keep the states where they are");
+
             for (name, value) in self.force.dict_mut() {
                 match value {
                     Value::Number(value, range) => {
@@ -182,7 +799,7 @@ impl MainApp {
             //     .on_hover_text("Layout the graph")
             //     .clicked()
             // {
-            //     self.layout.layout(&mut self.graph);
+            //     self.layout.layout(&mut self.graph, None);
             // }
         });
     }
@@ -205,39 +822,180 @@ impl eframe::App for MainApp {
             }
         }
 
+        if let Some(error) = self.error.as_mut() {
+            let mut is_open = true;
+            error.show(ctx, &mut is_open);
+
+            if !is_open {
+                self.error = None;
+            }
+        }
+
+        if let Some(format) = self.format.as_mut() {
+            let mut is_open = true;
+            let chosen = format.show(ctx, &mut is_open);
+            let path = format.path.clone();
+
+            if !is_open {
+                self.format = None;
+            }
+            if let Some(chosen) = chosen {
+                self.open_as(chosen, path);
+            }
+        }
+
+        if let Some(progress) = self.progress.as_mut() {
+            let mut is_open = true;
+            let result = progress.show(ctx, &mut is_open);
+
+            if !is_open {
+                self.progress = None;
+            }
+            match result {
+                Some(message) => self.finish_loading(message),
+                // cancelled
+                None if !is_open => self.pending_session = None,
+                None => {}
+            }
+        }
+
+        if let Some(listen) = self.listen.as_mut() {
+            let mut is_open = true;
+            let source = listen.show(ctx, &mut is_open);
+
+            if !is_open {
+                self.listen = None;
+            }
+            if let Some(source) = source {
+                self.listen(source);
+            }
+        }
+
+        self.hypergraphs.retain_mut(|hypergraph| {
+            let mut is_open = true;
+            hypergraph.show(ctx, &mut is_open);
+            is_open
+        });
+
+        if let Some(repair) = self.repair.as_mut() {
+            let mut is_open = true;
+            let repaired = repair.show(ctx, &mut is_open);
+
+            if !is_open {
+                self.repair = None;
+            }
+            if let Some(state_space) = repaired {
+                self.load_state_space(state_space);
+            }
+        }
+
         #[cfg(not(target_arch = "wasm32"))] // no File->Quit on web pages!
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             // The top panel is often a good place for a menu bar:
             egui::menu::bar(ui, |ui| {
                 ui.menu_button("File", |ui| {
-                    if ui.button("Open Kripke").clicked() {
-                        if let Some(path) = rfd::FileDialog::new()
-                            .add_filter("Kripke json", &["json"])
-                            .pick_file()
-                        {
-                            self.file = Some(path);
+                    if ui.button("Open…").clicked() {
+                        ui.close_menu();
+                        let mut dialog = rfd::FileDialog::new().add_filter("All files", &["*"]);
+                        for format in FORMATS.iter().filter(|f| !f.extensions.is_empty()) {
+                            dialog = dialog.add_filter(format.name, format.extensions);
+                        }
+                        if let Some(path) = dialog.pick_file() {
+                            self.open(path);
                         }
                     }
-                    if ui.button("Open slim States").clicked() {
+                    ui.menu_button("Open as", |ui| {
+                        for format in FORMATS {
+                            if ui.button(format.name).clicked() {
+                                ui.close_menu();
+                                let extensions = match format.extensions.is_empty() {
+                                    true => &["*"],
+                                    false => format.extensions,
+                                };
+                                if let Some(path) = rfd::FileDialog::new()
+                                    .add_filter(format.name, extensions)
+                                    .pick_file()
+                                {
+                                    self.open_as(format, path);
+                                }
+                            }
+                        }
+                    });
+                    ui.add_enabled(
+                        self.file.is_some(),
+                        egui::Checkbox::new(&mut self.watch_file, "Reload on change"),
+                    );
+                    ui.separator();
+                    if ui.button("Listen…").clicked() {
+                        ui.close_menu();
+                        self.listen = Some(LiveWindow::default());
+                    }
+                    if ui
+                        .add_enabled(self.live.is_some(), egui::Button::new("Stop listening"))
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.live = None;
+                    }
+                    ui.separator();
+                    if ui.button("Open session…").clicked() {
+                        ui.close_menu();
                         if let Some(path) = rfd::FileDialog::new()
-                            .add_filter("slim dump", &["*"])
+                            .add_filter("Graphew session", &["graphew"])
                             .pick_file()
                         {
-                            let file = std::fs::read_to_string(path).unwrap();
-                            if let Ok(state_space) = SLIMTranslator::translate(&file) {
-                                self.graph = state_space.into();
-                                self.sim = construct_simulation(&self.graph);
-                                self.loaded = true;
-                            }
+                            self.open_session(path);
                         }
                     }
-                    if ui.button("Open SPIN States").clicked() {
-                        // todo
+                    if ui
+                        .add_enabled(
+                            self.loaded && self.file_format.is_some(),
+                            egui::Button::new("Save session…"),
+                        )
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.save_session();
                     }
+                    ui.separator();
                     if ui.button("Quit").clicked() {
                         _frame.close();
                     }
                 });
+                ui.menu_button("Export", |ui| {
+                    ui.add_enabled_ui(self.loaded, |ui| {
+                        if ui.button("DOT").clicked() {
+                            ui.close_menu();
+                            self.export_dot(false);
+                        }
+                        if ui
+                            .button("DOT with positions")
+                            .on_hover_text("Keeps the layout with `neato -n`")
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.export_dot(true);
+                        }
+                        ui.separator();
+                        if ui.button("GraphML").clicked() {
+                            ui.close_menu();
+                            self.save("GraphML", "graphml", to_graphml(&self.graph, &self.sim));
+                        }
+                        if ui.button("JSON").clicked() {
+                            ui.close_menu();
+                            self.save("JSON", "json", to_json(&self.graph, &self.sim));
+                        }
+                        ui.separator();
+                        if ui
+                            .button("SVG")
+                            .on_hover_text("Renders the current view")
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.export_svg();
+                        }
+                    });
+                });
                 ui.menu_button("Help", |ui| {
                     if ui
                         .button("About")
@@ -252,7 +1010,22 @@ impl eframe::App for MainApp {
 
         egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                // todo: file type
+                if let (Some(file), Some(format)) = (&self.file, self.file_format) {
+                    ui.label(format!("{} ({})", file.display(), format));
+                }
+                if let Some(live) = &self.live {
+                    match &live.peer {
+                        Some(peer) => ui.label(format!("receiving from {}", peer)),
+                        None => ui.label(format!("listening on {}", live.source)),
+                    };
+                    if let Some(error) = &live.last_invalid {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("{} invalid lines", live.invalid),
+                        )
+                        .on_hover_text(format!("Last: {}", error));
+                    }
+                }
                 egui::warn_if_debug_build(ui);
             });
         });
@@ -264,14 +1037,52 @@ impl eframe::App for MainApp {
 
             ui.collapsing("selected", |ui| {
                 ScrollArea::vertical().max_height(200.).show(ui, |ui| {
-                    self.selected_nodes.iter().for_each(|node| {
-                        if let Some(state) = node.data() {
-                            ui.label(format!("{}", state.info));
+                    self.selected_nodes.iter().for_each(|idx| {
+                        if let Some(state) = self.graph.node_weight(*idx).and_then(|n| n.data()) {
+                            ui.label(&state.info);
+                            if let Some(process) = &state.lmntal {
+                                egui::CollapsingHeader::new("structure")
+                                    .id_source(("structure", state.index))
+                                    .show(ui, |ui| process_tree(ui, process, state.index));
+                            }
                         }
+                        // outgoing transitions with their labels
+                        self.graph.edges(*idx).for_each(|e| {
+                            let target = self
+                                .graph
+                                .node_weight(e.target())
+                                .and_then(|n| n.data())
+                                .map(|state| state.index);
+                            if let (Some(target), Some(transition)) = (target, e.weight().data()) {
+                                match transition.caption() {
+                                    Some(caption) => ui.small(format!("→ {}: {}", target, caption)),
+                                    None => ui.small(format!("→ {}", target)),
+                                };
+                            }
+                        });
+                        ui.separator();
                     });
                 });
             });
 
+            ui.collapsing("path", |ui| {
+                self.path.ui(ui, &self.graph, &self.selected_nodes);
+            });
+
+            ui.collapsing("components", |ui| {
+                if self.components.ui(ui, &mut self.graph) {
+                    self.color_states();
+                }
+            });
+
+            ui.collapsing("deadlocks", |ui| {
+                self.deadlocks.ui(ui, &mut self.graph);
+            });
+
+            ui.collapsing("CTL", |ui| {
+                self.ctl.ui(ui, &mut self.graph);
+            });
+
             ui.with_layout(Layout::bottom_up(egui::Align::Min), |ui| {
                 if ui
                     .button({
@@ -287,8 +1098,15 @@ impl eframe::App for MainApp {
             });
         });
 
+        self.update_condensation();
+
         egui::CentralPanel::default().show(ctx, |ui| {
-            if self.loaded {
+            if let Some(condensation) = &mut self.condensation {
+                if condensation.ui(ui, &self.graph) {
+                    self.refresh_condensation();
+                    self.color_states();
+                }
+            } else if self.loaded {
                 let interaction_settings = SettingsInteraction::new()
                     .with_dragging_enabled(true)
                     .with_clicking_enabled(true)
@@ -314,12 +1132,67 @@ impl eframe::App for MainApp {
                     .with_styles(&style_settings)
                     .with_changes(&self.changes_sender);
 
-                ui.add(&mut graph);
+                let resp = ui.add(&mut graph);
+                self.view_rect = resp.rect;
+                let transform = ViewTransform::fit_to_screen(
+                    &self.graph,
+                    resp.rect,
+                    self.settings_navigation.screen_padding,
+                );
+                self.view_transform = transform;
+
+                if self.settings_style.edge_labels_always {
+                    draw_edge_labels(
+                        &ui.painter_at(resp.rect),
+                        &self.graph,
+                        &transform,
+                        ui.visuals().text_color(),
+                    );
+                }
+
+                for overlay in self.overlays() {
+                    overlay.draw(&ui.painter_at(resp.rect), &self.graph, &transform);
+                }
+
+                if let Some((diff, since)) = &self.reload_diff {
+                    let elapsed = ui.input(|i| i.time) - since;
+                    if elapsed < RELOAD_HIGHLIGHT {
+                        let opacity = (1. - elapsed / RELOAD_HIGHLIGHT) as f32;
+                        draw_reload_diff(
+                            &ui.painter_at(resp.rect),
+                            &self.graph,
+                            &transform,
+                            diff,
+                            opacity,
+                        );
+                        ui.ctx().request_repaint();
+                    } else {
+                        self.reload_diff = None;
+                    }
+                }
             }
         });
 
+        self.watch();
+        self.update_live();
         self.handle_changes();
         self.sync_graph_with_simulation();
-        self.update_simulation();
+        if !self.layout_paused {
+            self.update_simulation();
+        }
+    }
+}
+
+/// Copy of `node` drawn with the default color.
+fn without_color(node: &Node<State>) -> Node<State> {
+    let mut plain = Node::default();
+    plain.set_data(node.data().cloned());
+    plain.set_location(node.location());
+    plain.set_folded(node.folded());
+    plain.set_selected(node.selected());
+    plain.set_dragged(node.dragged());
+    match node.label() {
+        Some(label) => plain.with_label(label.clone()),
+        None => plain,
     }
 }
@@ -0,0 +1,36 @@
+use egui::Color32;
+use egui_graphs::Node;
+
+use crate::{graph::state::State, settings::SettingsStyle};
+
+pub mod dot;
+pub mod graphml;
+pub mod json;
+pub mod svg;
+
+/// Color a state is highlighted with, in the same order of precedence as in the view.
+fn state_color(state: &State, style: &SettingsStyle) -> Option<Color32> {
+    match state {
+        state if state.initial => Some(style.color_initial),
+        state if state.accepting => Some(style.color_accepting),
+        state if state.deadlock => Some(style.color_deadlock),
+        _ => None,
+    }
+}
+
+fn hex(color: Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+/// Escapes `s` for XML text and attribute values.
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Id of the state behind `node` in exported files.
+fn node_id(node: &Node<State>) -> Option<usize> {
+    node.data().map(|state| state.index)
+}
@@ -0,0 +1,91 @@
+use std::fmt::Write;
+
+use egui_graphs::Graph;
+use petgraph::Directed;
+
+use crate::{
+    graph::{state::State, transition::Transition},
+    settings::SettingsStyle,
+};
+
+use super::{hex, node_id, state_color};
+
+/// Distance of the start marker from the initial state it points at.
+const START_OFFSET: f32 = 30.;
+
+/// Writes `graph` in the Graphviz DOT format.
+///
+/// Transitions keep their label in `label` and their weight in `weight`.
+/// Initial states are the targets of an unlabelled `point` node, accepting
+/// states are drawn as double circles and selected states with a thick
+/// outline, so the file reads back into Graphew with the same markers. With
+/// `positions` every node gets the `pos` attribute of its current location,
+/// which `neato -n` keeps as it is.
+pub fn to_dot(
+    graph: &Graph<State, Transition, Directed>,
+    style: &SettingsStyle,
+    positions: bool,
+) -> String {
+    let mut dot = String::from("digraph statespace {\n    node [shape=circle];\n");
+    // Graphviz points grow upwards, egui points downwards
+    let pos = |x: f32, y: f32| format!("pos=\"{:.2},{:.2}\"", x, -y);
+
+    for node in graph.node_weights() {
+        let (Some(state), Some(id)) = (node.data(), node_id(node)) else {
+            continue;
+        };
+        let mut attrs = vec![format!("label=\"{}\"", escape(&state.info))];
+        if state.accepting {
+            attrs.push("shape=doublecircle".to_string());
+        }
+        if let Some(color) = state_color(state, style) {
+            attrs.push(format!("color=\"{}\"", hex(color)));
+        }
+        if node.selected() {
+            attrs.push("penwidth=3".to_string());
+        }
+        if positions {
+            attrs.push(pos(node.location().x, node.location().y));
+        }
+        writeln!(dot, "    {} [{}];", id, attrs.join(", ")).unwrap();
+
+        if state.initial {
+            let mut start = vec!["shape=point".to_string(), "label=\"\"".to_string()];
+            if positions {
+                start.push(pos(node.location().x - START_OFFSET, node.location().y));
+            }
+            writeln!(dot, "    __start{} [{}];", id, start.join(", ")).unwrap();
+            writeln!(dot, "    __start{} -> {};", id, id).unwrap();
+        }
+    }
+
+    for idx in graph.edge_indices() {
+        let (source, target) = graph.edge_endpoints(idx).unwrap();
+        let (Some(from), Some(to)) = (node_id(&graph[source]), node_id(&graph[target])) else {
+            continue;
+        };
+        let mut attrs = vec![];
+        if let Some(transition) = graph[idx].data() {
+            if let Some(label) = &transition.label {
+                attrs.push(format!("label=\"{}\"", escape(label)));
+            }
+            if let Some(weight) = transition.weight {
+                attrs.push(format!("weight=\"{}\"", weight));
+            }
+        }
+        match attrs.is_empty() {
+            true => writeln!(dot, "    {} -> {};", from, to).unwrap(),
+            false => writeln!(dot, "    {} -> {} [{}];", from, to, attrs.join(", ")).unwrap(),
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Escapes `s` for a quoted DOT string.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,103 @@
+use std::fmt::Write;
+
+use egui_graphs::Graph;
+use fdg_sim::Simulation;
+use petgraph::Directed;
+
+use crate::graph::{state::State, transition::Transition};
+
+use super::{escape_xml, node_id};
+
+const HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns"
+    xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+    xmlns:y="http://www.yworks.com/xml/graphml"
+    xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://www.yworks.com/xml/schema/graphml/1.1/ygraphml.xsd">
+  <key id="info" for="node" attr.name="info" attr.type="string"/>
+  <key id="props" for="node" attr.name="props" attr.type="string"/>
+  <key id="initial" for="node" attr.name="initial" attr.type="boolean"/>
+  <key id="accepting" for="node" attr.name="accepting" attr.type="boolean"/>
+  <key id="deadlock" for="node" attr.name="deadlock" attr.type="boolean"/>
+  <key id="x" for="node" attr.name="x" attr.type="float"/>
+  <key id="y" for="node" attr.name="y" attr.type="float"/>
+  <key id="graphics" for="node" yfiles.type="nodegraphics"/>
+  <key id="label" for="edge" attr.name="label" attr.type="string"/>
+  <key id="weight" for="edge" attr.name="weight" attr.type="double"/>
+  <graph id="statespace" edgedefault="directed">
+"#;
+
+/// Size of the node shapes read by yEd.
+const NODE_SIZE: f32 = 30.;
+
+/// Writes `graph` in the GraphML format, with the coordinates of every state
+/// taken from `sim`.
+///
+/// The coordinates are stored as the `x` and `y` attributes read by Gephi and
+/// as node graphics read by yEd.
+pub fn to_graphml(
+    graph: &Graph<State, Transition, Directed>,
+    sim: &Simulation<State, f32>,
+) -> String {
+    let mut xml = String::from(HEADER);
+
+    for idx in graph.node_indices() {
+        let (Some(state), Some(id)) = (graph[idx].data(), node_id(&graph[idx])) else {
+            continue;
+        };
+        let location = sim.get_graph()[idx].location;
+        writeln!(xml, "    <node id=\"s{}\">", id).unwrap();
+        data(&mut xml, "info", &escape_xml(&state.info));
+        data(&mut xml, "props", &escape_xml(&state.props.join(", ")));
+        data(&mut xml, "initial", &state.initial.to_string());
+        data(&mut xml, "accepting", &state.accepting.to_string());
+        data(&mut xml, "deadlock", &state.deadlock.to_string());
+        data(&mut xml, "x", &location.x.to_string());
+        data(&mut xml, "y", &location.y.to_string());
+        writeln!(
+            xml,
+            "      <data key=\"graphics\"><y:ShapeNode>\
+             <y:Geometry x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>\
+             <y:NodeLabel>{}</y:NodeLabel>\
+             <y:Shape type=\"ellipse\"/>\
+             </y:ShapeNode></data>",
+            location.x - NODE_SIZE / 2.,
+            location.y - NODE_SIZE / 2.,
+            NODE_SIZE,
+            NODE_SIZE,
+            escape_xml(&state.info),
+        )
+        .unwrap();
+        xml.push_str("    </node>\n");
+    }
+
+    for idx in graph.edge_indices() {
+        let (source, target) = graph.edge_endpoints(idx).unwrap();
+        let (Some(from), Some(to)) = (node_id(&graph[source]), node_id(&graph[target])) else {
+            continue;
+        };
+        writeln!(
+            xml,
+            "    <edge id=\"e{}\" source=\"s{}\" target=\"s{}\">",
+            idx.index(),
+            from,
+            to
+        )
+        .unwrap();
+        if let Some(transition) = graph[idx].data() {
+            if let Some(label) = &transition.label {
+                data(&mut xml, "label", &escape_xml(label));
+            }
+            if let Some(weight) = transition.weight {
+                data(&mut xml, "weight", &weight.to_string());
+            }
+        }
+        xml.push_str("    </edge>\n");
+    }
+
+    xml.push_str("  </graph>\n</graphml>\n");
+    xml
+}
+
+fn data(xml: &mut String, key: &str, value: &str) {
+    writeln!(xml, "      <data key=\"{}\">{}</data>", key, value).unwrap();
+}
@@ -0,0 +1,88 @@
+use egui_graphs::Graph;
+use fdg_sim::Simulation;
+use petgraph::Directed;
+use serde::Serialize;
+
+use crate::graph::{state::State, transition::Transition};
+
+use super::node_id;
+
+/// The schema read by [`KripkeJsonTranslator`](crate::graph::translator::KripkeJsonTranslator),
+/// extended by the layout and the computed flags.
+#[derive(Serialize)]
+struct StateSpaceJson<'a> {
+    states: Vec<StateJson<'a>>,
+    initial: Vec<usize>,
+    accepting: Vec<usize>,
+    transitions: Vec<TransitionJson<'a>>,
+}
+
+#[derive(Serialize)]
+struct StateJson<'a> {
+    id: usize,
+    info: &'a str,
+    props: &'a [String],
+    deadlock: bool,
+    x: f32,
+    y: f32,
+}
+
+#[derive(Serialize)]
+struct TransitionJson<'a> {
+    from: usize,
+    to: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weight: Option<f64>,
+}
+
+/// Writes `graph` as JSON, with the coordinates of every state taken from `sim`.
+pub fn to_json(graph: &Graph<State, Transition, Directed>, sim: &Simulation<State, f32>) -> String {
+    let mut json = StateSpaceJson {
+        states: vec![],
+        initial: vec![],
+        accepting: vec![],
+        transitions: vec![],
+    };
+
+    for idx in graph.node_indices() {
+        let Some(state) = graph[idx].data() else {
+            continue;
+        };
+        let location = sim.get_graph()[idx].location;
+        json.states.push(StateJson {
+            id: state.index,
+            info: &state.info,
+            props: &state.props,
+            deadlock: state.deadlock,
+            x: location.x,
+            y: location.y,
+        });
+        if state.initial {
+            json.initial.push(state.index);
+        }
+        if state.accepting {
+            json.accepting.push(state.index);
+        }
+    }
+
+    for idx in graph.edge_indices() {
+        let (source, target) = graph.edge_endpoints(idx).unwrap();
+        let (Some(from), Some(to), Some(transition)) = (
+            node_id(&graph[source]),
+            node_id(&graph[target]),
+            graph[idx].data(),
+        ) else {
+            continue;
+        };
+        json.transitions.push(TransitionJson {
+            from,
+            to,
+            label: transition.label.as_deref(),
+            weight: transition.weight,
+        });
+    }
+
+    serde_json::to_string_pretty(&json).unwrap()
+}
@@ -0,0 +1,395 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    f32::consts::{PI, TAU},
+    fmt::Write,
+};
+
+use egui::{Color32, Pos2, Rect, Vec2, Visuals};
+use egui_graphs::Graph;
+use petgraph::{
+    stable_graph::{EdgeIndex, NodeIndex},
+    visit::EdgeRef,
+    Directed, Direction,
+};
+
+use crate::{
+    settings::SettingsStyle,
+    views::overlay::{path_stroke, ring_radius, Disk, Overlay, ViewTransform, RING_WIDTH},
+};
+
+use super::{escape_xml, hex};
+
+// sizes and colors as drawn by egui_graphs
+const NODE_RADIUS: f32 = 5.;
+const TIP_ANGLE: f32 = TAU / 50.;
+const COLOR_SELECTION: [u8; 4] = [0, 255, 127, 153];
+const COLOR_SELECTION_CHILD: [u8; 4] = [100, 149, 237, 153];
+const COLOR_SELECTION_PARENT: [u8; 4] = [255, 105, 180, 153];
+const COLOR_DRAG: [u8; 4] = [240, 128, 128, 153];
+const COLOR_LIGHT: Color32 = Color32::from_rgb(200, 200, 200);
+const COLOR_DARK: Color32 = Color32::from_rgb(128, 128, 128);
+const EDGE_LABEL_SIZE: f32 = 10.;
+
+/// What the view currently shows.
+pub struct SvgView<'a> {
+    /// Area the graph is drawn in.
+    pub rect: Rect,
+    /// Zoom and pan the graph is drawn with.
+    pub transform: ViewTransform,
+    pub selection_depth: i32,
+    pub style: &'a SettingsStyle,
+    pub visuals: &'a Visuals,
+    /// Highlights drawn over the graph, from the bottom up.
+    pub overlays: Vec<Overlay<'a>>,
+    /// Circles drawn behind the graph.
+    pub backdrop: Vec<Disk>,
+}
+
+/// Renders `graph` as SVG the way it is shown in `view`, with the edges
+/// labelled by `caption` if edge labels are always shown.
+pub fn to_svg<N: Clone, E: Clone>(
+    graph: &Graph<N, E, Directed>,
+    view: &SvgView<'_>,
+    caption: impl Fn(&E) -> Option<String>,
+) -> String {
+    let transform = view.transform;
+    let origin = view.rect.min.to_vec2();
+    let pos = |idx: NodeIndex| transform.apply(graph[idx].location()) - origin;
+    let radius = |idx: NodeIndex| {
+        let edges = graph.edges(idx).count() as f32;
+        (NODE_RADIUS + view.style.edge_radius_weight * edges) * transform.zoom
+    };
+    let dark = view.visuals.dark_mode;
+    let (node_color, edge_color, label_color) = match dark {
+        true => (COLOR_LIGHT, COLOR_DARK, Color32::WHITE),
+        false => (COLOR_DARK, COLOR_LIGHT, Color32::BLACK),
+    };
+    let (sub_nodes, sub_edges) = subselection(graph, view.selection_depth);
+    let highlight = rgba(match view.selection_depth > 0 {
+        true => COLOR_SELECTION_CHILD,
+        false => COLOR_SELECTION_PARENT,
+    });
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = view.rect.width(),
+        h = view.rect.height(),
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"  <rect width="100%" height="100%" fill="{}"/>"#,
+        hex(view.visuals.panel_fill)
+    )
+    .unwrap();
+
+    for disk in &view.backdrop {
+        let center = pos(disk.node);
+        writeln!(
+            svg,
+            r#"  <circle cx="{}" cy="{}" r="{}" {}{}/>"#,
+            center.x,
+            center.y,
+            disk.radius,
+            fill(disk.fill),
+            match disk.stroke.width > 0. {
+                true => format!(" {}", stroke(disk.stroke.color, disk.stroke.width)),
+                false => String::new(),
+            },
+        )
+        .unwrap();
+    }
+
+    // edges between the same pair of nodes are curved further apart
+    let mut orders: HashMap<(NodeIndex, NodeIndex), usize> = HashMap::new();
+    let mut edges = vec![];
+    for idx in graph.edge_indices() {
+        let endpoints = graph.edge_endpoints(idx).unwrap();
+        let order = orders.entry(endpoints).or_default();
+        edges.push((idx, endpoints, *order));
+        *order += 1;
+    }
+
+    let mut highlighted = String::new();
+    for (idx, (source, target), order) in edges {
+        // egui_graphs curves the first edge of a pair the most
+        let order = orders[&(source, target)] - 1 - order;
+        let edge = &graph[idx];
+        let (color, width, out) = match sub_edges.contains(&idx) {
+            true => (highlight, edge.width() * 2., &mut highlighted),
+            false => (edge.color().unwrap_or(edge_color), edge.width(), &mut svg),
+        };
+        let stroke = stroke(color, width * transform.zoom);
+
+        if source == target {
+            let (center, r) = (pos(source), radius(source));
+            let angle = PI / 4.;
+            let y = center.y - r * angle.sin();
+            let loop_size = r * (5. + order as f32);
+            writeln!(
+                out,
+                r#"  <path d="M {} {} C {} {} {} {} {} {}" fill="none" {}/>"#,
+                center.x + r * angle.cos(),
+                y,
+                center.x + loop_size,
+                center.y - loop_size,
+                center.x - loop_size,
+                center.y - loop_size,
+                center.x - r * angle.cos(),
+                y,
+                stroke,
+            )
+            .unwrap();
+            continue;
+        }
+
+        let (start, end) = (pos(source), pos(target));
+        let dir = (end - start).normalized();
+        let start = start + dir * radius(source);
+        let tip = end - dir * radius(target);
+        let tip_size = edge.tip_size() * transform.zoom;
+
+        let back = match order {
+            0 => {
+                line(out, start, tip, &stroke);
+                dir
+            }
+            _ => {
+                let perpendicular = Vec2::new(-dir.y, dir.x);
+                let control = start
+                    + (tip - start) / 2.
+                    + perpendicular * edge.curve_size() * transform.zoom * order as f32;
+                writeln!(
+                    out,
+                    r#"  <path d="M {} {} Q {} {} {} {}" fill="none" {}/>"#,
+                    start.x, start.y, control.x, control.y, tip.x, tip.y, stroke,
+                )
+                .unwrap();
+                -(control - tip).normalized()
+            }
+        };
+        for angle in [TIP_ANGLE, -TIP_ANGLE] {
+            line(out, tip, tip - rotate(back, angle) * tip_size, &stroke);
+        }
+    }
+
+    for idx in graph.node_indices() {
+        let node = &graph[idx];
+        let (center, r) = (pos(idx), radius(idx));
+        let interacted = node.selected() || node.dragged() || sub_nodes.contains(&idx);
+        let out = match interacted {
+            true => &mut highlighted,
+            false => &mut svg,
+        };
+        let color = node.color().unwrap_or(node_color);
+        writeln!(
+            out,
+            r#"  <circle cx="{}" cy="{}" r="{}" {} {}/>"#,
+            center.x,
+            center.y,
+            r,
+            fill(color),
+            stroke(color, 1.),
+        )
+        .unwrap();
+
+        if !(interacted || view.style.labels_always) {
+            continue;
+        }
+        if let Some(label) = node.label() {
+            text(
+                out,
+                Pos2::new(center.x, center.y - 2. * r),
+                label,
+                r,
+                label_color,
+                "start",
+                "hanging",
+            );
+        }
+
+        let ring = match node {
+            node if node.dragged() => rgba(COLOR_DRAG),
+            node if node.selected() => rgba(COLOR_SELECTION),
+            _ if sub_nodes.contains(&idx) => highlight,
+            _ => continue,
+        };
+        writeln!(
+            out,
+            r#"  <circle cx="{}" cy="{}" r="{}" fill="none" {}/>"#,
+            center.x,
+            center.y,
+            r * 1.5,
+            stroke(ring, r),
+        )
+        .unwrap();
+    }
+    svg.push_str(&highlighted);
+
+    if view.style.edge_labels_always {
+        for idx in graph.edge_indices() {
+            let Some(caption) = graph[idx].data().and_then(&caption) else {
+                continue;
+            };
+            let (source, target) = graph.edge_endpoints(idx).unwrap();
+            let (start, end) = (pos(source), pos(target));
+            // loops are labelled above their node, as in the overlay
+            let at = match source == target {
+                true => start - Vec2::new(0., 20. * transform.zoom),
+                false => start + (end - start) / 2.,
+            };
+            text(
+                &mut svg,
+                at,
+                &caption,
+                EDGE_LABEL_SIZE,
+                view.visuals.text_color(),
+                "middle",
+                "text-after-edge",
+            );
+        }
+    }
+
+    let ring = ring_radius(transform.zoom);
+    for overlay in &view.overlays {
+        match overlay {
+            Overlay::Rings(states, color) => {
+                for idx in states.iter().filter(|idx| graph.contains_node(**idx)) {
+                    circle(&mut svg, pos(*idx), ring, &stroke(*color, RING_WIDTH));
+                }
+            }
+            Overlay::Path(path) => {
+                let path_stroke = path_stroke(transform.zoom);
+                let path_stroke = stroke(path_stroke.color, path_stroke.width);
+                let points = path
+                    .nodes
+                    .iter()
+                    .filter(|idx| graph.contains_node(**idx))
+                    .map(|idx| pos(*idx))
+                    .collect::<Vec<_>>();
+                for segment in points.windows(2) {
+                    line(&mut svg, segment[0], segment[1], &path_stroke);
+                }
+                for point in points {
+                    circle(&mut svg, point, ring, &path_stroke);
+                }
+            }
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Nodes and edges highlighted around the selected nodes, walking
+/// `depth` steps forwards or, if negative, backwards.
+fn subselection<N: Clone, E: Clone>(
+    graph: &Graph<N, E, Directed>,
+    depth: i32,
+) -> (HashSet<NodeIndex>, HashSet<EdgeIndex>) {
+    let (mut nodes, mut edges) = (HashSet::new(), HashSet::new());
+    let direction = match depth > 0 {
+        true => Direction::Outgoing,
+        false => Direction::Incoming,
+    };
+    let steps = depth.unsigned_abs() as usize;
+
+    for root in graph.node_indices().filter(|idx| graph[*idx].selected()) {
+        let mut queue = VecDeque::from([(root, 0)]);
+        let mut visited = HashSet::from([root]);
+        while let Some((idx, step)) = queue.pop_front() {
+            if step == steps {
+                continue;
+            }
+            for edge in graph.edges_directed(idx, direction) {
+                edges.insert(edge.id());
+                let next = match direction {
+                    Direction::Outgoing => edge.target(),
+                    Direction::Incoming => edge.source(),
+                };
+                if visited.insert(next) {
+                    nodes.insert(next);
+                    queue.push_back((next, step + 1));
+                }
+            }
+        }
+    }
+
+    (nodes, edges)
+}
+
+fn rotate(v: Vec2, angle: f32) -> Vec2 {
+    let (sin, cos) = angle.sin_cos();
+    Vec2::new(cos * v.x - sin * v.y, sin * v.x + cos * v.y)
+}
+
+fn rgba([r, g, b, a]: [u8; 4]) -> Color32 {
+    Color32::from_rgba_unmultiplied(r, g, b, a)
+}
+
+fn opacity(color: Color32) -> f32 {
+    color.a() as f32 / 255.
+}
+
+fn fill(color: Color32) -> String {
+    let [r, g, b, _] = color.to_srgba_unmultiplied();
+    format!(
+        r#"fill="{}" fill-opacity="{}""#,
+        hex(Color32::from_rgb(r, g, b)),
+        opacity(color)
+    )
+}
+
+fn stroke(color: Color32, width: f32) -> String {
+    let [r, g, b, _] = color.to_srgba_unmultiplied();
+    format!(
+        r#"stroke="{}" stroke-opacity="{}" stroke-width="{}""#,
+        hex(Color32::from_rgb(r, g, b)),
+        opacity(color),
+        width
+    )
+}
+
+fn line(out: &mut String, from: Pos2, to: Pos2, stroke: &str) {
+    writeln!(
+        out,
+        r#"  <line x1="{}" y1="{}" x2="{}" y2="{}" {}/>"#,
+        from.x, from.y, to.x, to.y, stroke
+    )
+    .unwrap();
+}
+
+/// An unfilled circle.
+fn circle(out: &mut String, center: Pos2, r: f32, stroke: &str) {
+    writeln!(
+        out,
+        r#"  <circle cx="{}" cy="{}" r="{}" fill="none" {}/>"#,
+        center.x, center.y, r, stroke
+    )
+    .unwrap();
+}
+
+fn text(
+    out: &mut String,
+    at: Pos2,
+    content: &str,
+    size: f32,
+    color: Color32,
+    anchor: &str,
+    baseline: &str,
+) {
+    writeln!(
+        out,
+        r#"  <text x="{}" y="{}" font-family="monospace" font-size="{}" {} text-anchor="{}" dominant-baseline="{}">{}</text>"#,
+        at.x,
+        at.y,
+        size,
+        fill(color),
+        anchor,
+        baseline,
+        escape_xml(content)
+    )
+    .unwrap();
+}
@@ -1,33 +1,100 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use egui_graphs::{to_input_graph, Graph};
 use fdg_sim::{ForceGraph, ForceGraphHelper, Simulation, SimulationParameters};
 use petgraph::{stable_graph::StableGraph, visit::IntoNodeReferences, Directed};
 
+pub mod lmntal;
 pub mod state;
 pub mod transition;
 pub mod translator;
+pub mod validation;
+
+use validation::ValidationReport;
 
 #[derive(Debug, Clone)]
 pub struct StateSpace {
     pub states: Vec<state::State>,
     pub transitions: Vec<transition::Transition>,
+    /// Indices of the initial states.
+    pub initial: HashSet<usize>,
+    /// Indices of the accepting states, e.g. of a Büchi product.
+    pub accepting: HashSet<usize>,
 }
 
-impl Into<Graph<state::State, transition::Transition, Directed>> for StateSpace {
-    fn into(self) -> Graph<state::State, transition::Transition, Directed> {
+impl StateSpace {
+    pub fn new(states: Vec<state::State>, transitions: Vec<transition::Transition>) -> Self {
+        Self {
+            states,
+            transitions,
+            initial: HashSet::new(),
+            accepting: HashSet::new(),
+        }
+    }
+
+    pub fn with_initial(mut self, initial: impl IntoIterator<Item = usize>) -> Self {
+        self.initial = initial.into_iter().collect();
+        self
+    }
+
+    pub fn with_accepting(mut self, accepting: impl IntoIterator<Item = usize>) -> Self {
+        self.accepting = accepting.into_iter().collect();
+        self
+    }
+
+    /// Adds a part of a state space read by a [`translator::StreamTranslator`].
+    pub fn push(&mut self, event: translator::Event) {
+        match event {
+            translator::Event::State(state) => self.states.push(state),
+            translator::Event::Transition(transition) => self.transitions.push(transition),
+            translator::Event::Initial(index) => {
+                self.initial.insert(index);
+            }
+            translator::Event::Accepting(index) => {
+                self.accepting.insert(index);
+            }
+        }
+    }
+
+    /// Indices of the states without outgoing transitions that are not
+    /// accepting.
+    pub fn deadlocks(&self) -> HashSet<usize> {
+        let sources = self
+            .transitions
+            .iter()
+            .map(|transition| transition.from)
+            .collect::<HashSet<_>>();
+        self.states
+            .iter()
+            .map(|state| state.index)
+            .filter(|index| !sources.contains(index) && !self.accepting.contains(index))
+            .collect()
+    }
+}
+
+impl StateSpace {
+    /// Builds the graph of a state space whose [`validate`](Self::validate)
+    /// report is not fatal, so that the validation is not repeated.
+    ///
+    /// Panics if a transition points at an undeclared state.
+    pub fn to_graph(&self) -> Graph<state::State, transition::Transition, Directed> {
         let mut g = StableGraph::new();
         let mut idx_map = HashMap::new();
+        let deadlocks = self.deadlocks();
 
         for state in &self.states {
-            let idx = g.add_node(state.clone());
-            idx_map.insert(state.index, idx);
+            let mut state = state.clone();
+            state.initial = self.initial.contains(&state.index);
+            state.accepting = self.accepting.contains(&state.index);
+            state.deadlock = deadlocks.contains(&state.index);
+            let index = state.index;
+            idx_map.insert(index, g.add_node(state));
         }
 
         for transition in &self.transitions {
             g.add_edge(
-                idx_map.get(&transition.from).unwrap().clone(),
-                idx_map.get(&transition.to).unwrap().clone(),
+                idx_map[&transition.from],
+                idx_map[&transition.to],
                 transition.clone(),
             );
         }
@@ -36,6 +103,20 @@ impl Into<Graph<state::State, transition::Transition, Directed>> for StateSpace
     }
 }
 
+impl TryFrom<&StateSpace> for Graph<state::State, transition::Transition, Directed> {
+    type Error = ValidationReport;
+
+    /// Fails if a transition points at an undeclared state or a state index is
+    /// declared more than once. Unreachable states are allowed.
+    fn try_from(state_space: &StateSpace) -> Result<Self, Self::Error> {
+        let report = state_space.validate();
+        if report.is_fatal() {
+            return Err(report);
+        }
+        Ok(state_space.to_graph())
+    }
+}
+
 pub fn construct_simulation(
     g: &Graph<state::State, transition::Transition, Directed>,
 ) -> Simulation<state::State, f32> {
@@ -0,0 +1,564 @@
+use std::{collections::HashMap, fmt};
+
+/// Deepest nesting of atoms, lists and membranes a state may have, which
+/// keeps the parser within the stack of the loader thread.
+const MAX_DEPTH: usize = 256;
+
+/// The contents of an LMNtal state as printed by slim: atoms connected by
+/// links, nested in membranes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Process {
+    pub atoms: Vec<Atom>,
+    /// The membranes of the state. The first one is the state itself.
+    pub membranes: Vec<Membrane>,
+    pub links: Vec<Link>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Atom {
+    pub name: String,
+    /// Index of the membrane the atom is in.
+    pub membrane: usize,
+    /// Indices of the links at each argument.
+    pub args: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Membrane {
+    /// Empty for anonymous membranes.
+    pub name: String,
+    pub parent: Option<usize>,
+    /// Ids of the rulesets in the membrane, e.g. `@603`.
+    pub rulesets: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    /// Name in the state text, or a generated `_<index>` for links written
+    /// by nesting atoms.
+    pub name: String,
+    /// Whether the link is a hyperlink `!<name>`, which may have any number
+    /// of ends.
+    pub hyperlink: bool,
+    /// Atoms and argument positions the link connects.
+    pub ends: Vec<(usize, usize)>,
+}
+
+impl Process {
+    /// Whether an atom called `name` is anywhere in the state.
+    pub fn has_atom(&self, name: &str) -> bool {
+        self.atoms.iter().any(|atom| atom.name == name)
+    }
+
+    /// Indices of the atoms directly in `membrane`.
+    pub fn atoms_in(&self, membrane: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.atoms.len()).filter(move |i| self.atoms[*i].membrane == membrane)
+    }
+
+    /// Indices of the membranes directly in `membrane`.
+    pub fn children(&self, membrane: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.membranes.len()).filter(move |i| self.membranes[*i].parent == Some(membrane))
+    }
+
+    /// The atom at `index` written with its links, e.g. `append(L1, L2, _3)`.
+    pub fn describe_atom(&self, index: usize) -> String {
+        let atom = &self.atoms[index];
+        match atom.args.is_empty() {
+            true => atom.name.clone(),
+            false => format!(
+                "{}({})",
+                atom.name,
+                atom.args
+                    .iter()
+                    .map(|link| self.links[*link].name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// Error produced when a state is not valid LMNtal text. Columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LmntalError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for LmntalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for LmntalError {}
+
+/// Parses the text of a state such as `a(L0), {b(L0, c). @12}.`.
+///
+/// Nested atoms connect their last argument to the argument they are written
+/// at, and lists `[a, b | T]` are read as `'.'` and `'[]'` atoms.
+pub fn parse(text: &str) -> Result<Process, LmntalError> {
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        pos: 0,
+        process: Process::default(),
+        names: HashMap::new(),
+        depth: 0,
+    };
+    parser.process.membranes.push(Membrane {
+        name: String::new(),
+        parent: None,
+        rulesets: vec![],
+    });
+    parser.contents(0, None)?;
+    Ok(parser.process)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    process: Process,
+    /// Links by their name in the text.
+    names: HashMap<String, usize>,
+    /// Number of atoms, lists and membranes the parser is in.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> LmntalError {
+        LmntalError {
+            column: self.pos + 1,
+            message: message.into(),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), LmntalError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(self.error(format!("expected `{}`, found `{}`", expected, c))),
+            None => Err(self.error(format!("expected `{}`, found end of state", expected))),
+        }
+    }
+
+    /// Runs `read` one level of nesting deeper.
+    fn nested<T>(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> Result<T, LmntalError>,
+    ) -> Result<T, LmntalError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(format!("nested deeper than {} levels", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        let result = read(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Reads the atoms, membranes and rulesets of `membrane` up to `closing`.
+    fn contents(&mut self, membrane: usize, closing: Option<char>) -> Result<(), LmntalError> {
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None if closing.is_none() => return Ok(()),
+                None => return Err(self.error("unclosed membrane")),
+                Some(c) if Some(c) == closing => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(',' | '.') => self.pos += 1,
+                Some('{') => self.membrane(membrane, String::new())?,
+                Some('@') => {
+                    self.pos += 1;
+                    let id = self.word();
+                    self.process.membranes[membrane]
+                        .rulesets
+                        .push(format!("@{}", id));
+                }
+                Some(c) if is_link_start(c) => {
+                    // `X = Y` connects two links
+                    let atom = self.add_atom("=".to_string(), membrane);
+                    let link = self.link()?;
+                    self.add_arg(atom, link);
+                    self.expect('=')?;
+                    self.skip_whitespace();
+                    let link = self.arg(membrane)?;
+                    self.add_arg(atom, link);
+                }
+                Some(_) => {
+                    let name = self.functor()?;
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some('{') => self.membrane(membrane, name)?,
+                        _ => {
+                            self.atom(name, membrane, None)?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn membrane(&mut self, parent: usize, name: String) -> Result<(), LmntalError> {
+        self.expect('{')?;
+        let membrane = self.process.membranes.len();
+        self.process.membranes.push(Membrane {
+            name,
+            parent: Some(parent),
+            rulesets: vec![],
+        });
+        self.nested(|parser| parser.contents(membrane, Some('}')))?;
+        // stable membranes are printed as `{...}/`
+        if self.peek() == Some('/') {
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    /// Reads the arguments of the atom `name` and connects its last argument
+    /// to `parent` if it is nested.
+    fn atom(
+        &mut self,
+        name: String,
+        membrane: usize,
+        parent: Option<usize>,
+    ) -> Result<usize, LmntalError> {
+        let atom = self.add_atom(name, membrane);
+        self.skip_whitespace();
+        if self.peek() == Some('(') {
+            self.pos += 1;
+            loop {
+                let link = self.arg(membrane)?;
+                self.add_arg(atom, link);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(',') => self.pos += 1,
+                    Some(')') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.error("expected `,` or `)` after an argument")),
+                }
+            }
+        }
+        if let Some(link) = parent {
+            self.add_arg(atom, link);
+        }
+        Ok(atom)
+    }
+
+    /// Reads an argument and returns the link at it.
+    fn arg(&mut self, membrane: usize) -> Result<usize, LmntalError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if is_link_start(c) => self.link(),
+            Some('[') => {
+                self.pos += 1;
+                self.skip_whitespace();
+                if self.peek() == Some(']') {
+                    self.pos += 1;
+                    let link = self.anonymous_link();
+                    self.atom("[]".to_string(), membrane, Some(link))?;
+                    return Ok(link);
+                }
+                self.nested(|parser| parser.list(membrane))
+            }
+            Some(_) => {
+                let name = self.functor()?;
+                let link = self.anonymous_link();
+                self.nested(|parser| parser.atom(name, membrane, Some(link)))?;
+                Ok(link)
+            }
+            None => Err(self.error("expected an argument, found end of state")),
+        }
+    }
+
+    /// Reads the elements of a list after `[` and returns the link to its
+    /// first cell.
+    fn list(&mut self, membrane: usize) -> Result<usize, LmntalError> {
+        let first = self.anonymous_link();
+        let mut link = first;
+        loop {
+            let cell = self.add_atom(".".to_string(), membrane);
+            let head = self.arg(membrane)?;
+            self.add_arg(cell, head);
+            self.skip_whitespace();
+            let (tail, last) = match self.peek() {
+                Some(',') => {
+                    self.pos += 1;
+                    (self.anonymous_link(), false)
+                }
+                Some('|') => {
+                    self.pos += 1;
+                    let tail = self.arg(membrane)?;
+                    self.expect(']')?;
+                    (tail, true)
+                }
+                Some(']') => {
+                    self.pos += 1;
+                    let tail = self.anonymous_link();
+                    self.atom("[]".to_string(), membrane, Some(tail))?;
+                    (tail, true)
+                }
+                _ => return Err(self.error("expected `,`, `|` or `]` in a list")),
+            };
+            self.add_arg(cell, tail);
+            self.add_arg(cell, link);
+            match last {
+                true => return Ok(first),
+                false => link = tail,
+            }
+        }
+    }
+
+    /// Reads an atom name: a word, a number, or a quoted name or string.
+    fn functor(&mut self) -> Result<String, LmntalError> {
+        match self.peek() {
+            Some(quote @ ('\'' | '"')) => {
+                let start = self.pos;
+                self.pos += 1;
+                loop {
+                    match self.peek() {
+                        None => return Err(self.error("unclosed quote")),
+                        Some('\\') => self.pos += 2,
+                        Some(c) if c == quote => {
+                            self.pos += 1;
+                            break;
+                        }
+                        Some(_) => self.pos += 1,
+                    }
+                }
+                let name = self.slice(start, self.pos.min(self.chars.len()));
+                // quotes are only part of the name of strings
+                Ok(match quote {
+                    '\'' => name[1..name.len() - 1].to_string(),
+                    _ => name,
+                })
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let start = self.pos;
+                self.pos += 1;
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+                // a `.` is only part of a number if a digit follows
+                let fraction = self.chars.get(self.pos + 1).copied();
+                if self.peek() == Some('.') && fraction.is_some_and(|c| c.is_ascii_digit()) {
+                    self.pos += 1;
+                    while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                        self.pos += 1;
+                    }
+                }
+                Ok(self.slice(start, self.pos))
+            }
+            Some(c) if c.is_alphanumeric() || c == '_' => Ok(self.word()),
+            Some(c) => Err(self.error(format!("unexpected `{}`", c))),
+            None => Err(self.error("expected an atom, found end of state")),
+        }
+    }
+
+    fn word(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            self.pos += 1;
+        }
+        self.slice(start, self.pos)
+    }
+
+    fn slice(&self, start: usize, end: usize) -> String {
+        self.chars[start..end].iter().collect()
+    }
+
+    /// Reads a link name and returns the link of that name.
+    fn link(&mut self) -> Result<usize, LmntalError> {
+        let hyperlink = self.peek() == Some('!');
+        if hyperlink {
+            self.pos += 1;
+        }
+        let name = self.word();
+        if name.is_empty() {
+            return Err(self.error("expected a link name"));
+        }
+        let name = match hyperlink {
+            true => format!("!{}", name),
+            false => name,
+        };
+        if let Some(link) = self.names.get(&name) {
+            return Ok(*link);
+        }
+        let link = self.process.links.len();
+        self.process.links.push(Link {
+            name: name.clone(),
+            hyperlink,
+            ends: vec![],
+        });
+        self.names.insert(name, link);
+        Ok(link)
+    }
+
+    fn anonymous_link(&mut self) -> usize {
+        let link = self.process.links.len();
+        self.process.links.push(Link {
+            name: format!("_{}", link),
+            hyperlink: false,
+            ends: vec![],
+        });
+        link
+    }
+
+    fn add_atom(&mut self, name: String, membrane: usize) -> usize {
+        self.process.atoms.push(Atom {
+            name,
+            membrane,
+            args: vec![],
+        });
+        self.process.atoms.len() - 1
+    }
+
+    fn add_arg(&mut self, atom: usize, link: usize) {
+        let args = &mut self.process.atoms[atom].args;
+        self.process.links[link].ends.push((atom, args.len()));
+        args.push(link);
+    }
+}
+
+/// Link names start with an upper case letter or `_`, hyperlinks with `!`.
+fn is_link_start(c: char) -> bool {
+    c.is_uppercase() || c == '_' || c == '!'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(process: &Process) -> Vec<&str> {
+        process
+            .atoms
+            .iter()
+            .map(|atom| atom.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn nested_atoms_connect_their_last_argument() {
+        let process = parse("a(b(c, X)), d(X).").unwrap();
+        assert_eq!(names(&process), ["a", "b", "c", "d"]);
+        let [a, b, c, d] = [0, 1, 2, 3].map(|i| &process.atoms[i].args);
+        assert_eq!(a[0], b[2]);
+        assert_eq!(b[0], c[0]);
+        assert_eq!(b[1], d[0]);
+        assert_eq!(process.links[b[1]].name, "X");
+        assert_eq!(process.describe_atom(1), "b(_1, X, _0)");
+    }
+
+    #[test]
+    fn lists_are_cells() {
+        let process = parse("l([a, b]), e([]).").unwrap();
+        assert_eq!(names(&process), ["l", ".", "a", ".", "b", "[]", "e", "[]"]);
+        let [l, first, second, nil] = [0, 1, 3, 5].map(|i| &process.atoms[i].args);
+        assert_eq!(first[2], l[0]);
+        assert_eq!(first[1], second[2]);
+        assert_eq!(second[1], nil[0]);
+    }
+
+    #[test]
+    fn list_with_a_tail() {
+        let process = parse("l([a, b | T]), t(T).").unwrap();
+        assert_eq!(names(&process), ["l", ".", "a", ".", "b", "t"]);
+        assert_eq!(process.atoms[3].args[1], process.atoms[5].args[0]);
+        assert_eq!(process.links[process.atoms[5].args[0]].name, "T");
+    }
+
+    #[test]
+    fn long_list() {
+        let text = format!("l([{}a]).", "a, ".repeat(19_999));
+        let process = parse(&text).unwrap();
+        assert_eq!(
+            process.atoms.iter().filter(|a| a.name == ".").count(),
+            20_000
+        );
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error() {
+        let text = format!(
+            "{}a{}.",
+            "f(".repeat(MAX_DEPTH + 1),
+            ")".repeat(MAX_DEPTH + 1)
+        );
+        let error = parse(&text).unwrap_err();
+        assert!(error.message.starts_with("nested deeper"));
+        let text = format!("{}a{}.", "f(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH));
+        assert!(parse(&text).is_ok());
+    }
+
+    #[test]
+    fn hyperlinks() {
+        let process = parse("a(!H), b(!H), c(!H, L), d(L).").unwrap();
+        let hyperlink = &process.links[process.atoms[0].args[0]];
+        assert_eq!(hyperlink.name, "!H");
+        assert!(hyperlink.hyperlink);
+        assert_eq!(hyperlink.ends, [(0, 0), (1, 0), (2, 0)]);
+        assert!(!process.links[process.atoms[3].args[0]].hyperlink);
+    }
+
+    #[test]
+    fn membranes_and_rulesets() {
+        let process = parse("m{a. @603}/, {{b}. @12}. @1").unwrap();
+        let membranes = process
+            .membranes
+            .iter()
+            .map(|m| (m.name.as_str(), m.parent, m.rulesets.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            membranes,
+            [
+                ("", None, vec!["@1".to_string()]),
+                ("m", Some(0), vec!["@603".to_string()]),
+                ("", Some(0), vec!["@12".to_string()]),
+                ("", Some(2), vec![]),
+            ]
+        );
+        assert_eq!(process.atoms_in(1).collect::<Vec<_>>(), [0]);
+        assert_eq!(process.atoms_in(3).collect::<Vec<_>>(), [1]);
+        assert_eq!(process.children(0).collect::<Vec<_>>(), [1, 2]);
+    }
+
+    #[test]
+    fn quoted_names_and_numbers() {
+        let process = parse(r#"'a b'(X), "s\"t"(X), n(-2), f(1.5). 3."#).unwrap();
+        assert_eq!(
+            names(&process),
+            ["a b", r#""s\"t""#, "n", "-2", "f", "1.5", "3"]
+        );
+    }
+
+    #[test]
+    fn error_columns() {
+        let error = |text| parse(text).unwrap_err();
+        assert_eq!(
+            error("a(b"),
+            LmntalError {
+                column: 4,
+                message: "expected `,` or `)` after an argument".to_string()
+            }
+        );
+        assert_eq!(error("a(X, ]).").column, 6);
+        assert_eq!(error("{a").to_string(), "column 3: unclosed membrane");
+        assert_eq!(error("l([a b]).").column, 6);
+        assert_eq!(error("'a").message, "unclosed quote");
+    }
+}
@@ -1,11 +1,42 @@
+use std::sync::Arc;
+
+use super::lmntal::Process;
+
 #[derive(Debug, Clone)]
 pub struct State {
     pub index: usize,
     pub info: String,
+    /// Atomic propositions that hold in this state.
+    pub props: Vec<String>,
+    /// `info` parsed as an LMNtal process, for states read from slim dumps.
+    /// Shared, as states are copied into the simulation.
+    pub lmntal: Option<Arc<Process>>,
+    pub initial: bool,
+    pub accepting: bool,
+    /// Whether the state has no outgoing transitions and is not accepting.
+    pub deadlock: bool,
 }
 
 impl State {
     pub fn new(index: usize, info: String) -> Self {
-        Self { index, info }
+        Self {
+            index,
+            info,
+            props: vec![],
+            lmntal: None,
+            initial: false,
+            accepting: false,
+            deadlock: false,
+        }
+    }
+
+    pub fn with_props(mut self, props: Vec<String>) -> Self {
+        self.props = props;
+        self
+    }
+
+    pub fn with_lmntal(mut self, lmntal: Process) -> Self {
+        self.lmntal = Some(Arc::new(lmntal));
+        self
     }
 }
@@ -2,10 +2,39 @@
 pub struct Transition {
     pub from: usize,
     pub to: usize,
+    /// Action or rule name of the transition, if the format provides one.
+    pub label: Option<String>,
+    /// Probability or rate of the transition, if the format provides one.
+    pub weight: Option<f64>,
 }
 
 impl Transition {
     pub fn new(from: usize, to: usize) -> Self {
-        Self { from, to }
+        Self {
+            from,
+            to,
+            label: None,
+            weight: None,
+        }
+    }
+
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Text shown next to the edge of this transition.
+    pub fn caption(&self) -> Option<String> {
+        match (&self.label, self.weight) {
+            (Some(label), Some(weight)) => Some(format!("{} ({})", label, weight)),
+            (Some(label), None) => Some(label.clone()),
+            (None, Some(weight)) => Some(weight.to_string()),
+            (None, None) => None,
+        }
     }
 }
@@ -1,95 +1,860 @@
-use crate::graph::{state::State, transition::Transition};
+use std::{fmt, io::BufRead};
+
+use serde::Deserialize;
+
+use crate::graph::{lmntal, state::State, transition::Transition};
 
 use super::StateSpace;
 
+mod aut;
+mod dot;
+mod prism;
+pub mod registry;
+
+pub use aut::AutTranslator;
+pub use dot::DotTranslator;
+pub use prism::PrismTranslator;
+
 pub trait Translator {
     type ErrorType;
     fn translate(str: &str) -> Result<StateSpace, Self::ErrorType>;
 }
 
-pub struct SPINTranslator {
-    pub states: Vec<String>,
-    pub transitions: Vec<String>,
+/// Part of a state space, produced while an input is read.
+#[derive(Debug, Clone)]
+pub enum Event {
+    State(State),
+    Transition(Transition),
+    Initial(usize),
+    Accepting(usize),
+}
+
+/// Translator that reads its input line by line, so that the text of the
+/// input never has to be held in memory as a whole.
+///
+/// Memory is not bounded: [`translate_reader`](Self::translate_reader) and
+/// the loader still collect every state and transition, as all of them are
+/// drawn.
+pub trait StreamTranslator {
+    /// Reads `reader` and passes every state, transition and marker to `emit`
+    /// as soon as it is parsed.
+    fn translate_stream<R: BufRead>(reader: R, emit: impl FnMut(Event)) -> Result<(), ParseError>;
+
+    /// Collects the events read from `reader` into a [`StateSpace`].
+    fn translate_reader<R: BufRead>(reader: R) -> Result<StateSpace, ParseError> {
+        let mut state_space = StateSpace::new(vec![], vec![]);
+        Self::translate_stream(reader, |event| state_space.push(event))?;
+        if state_space.states.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        Ok(state_space)
+    }
+}
+
+/// Error produced when an input cannot be translated into a [`StateSpace`].
+///
+/// Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A token did not match what the format expects at this position.
+    Unexpected {
+        line: usize,
+        column: usize,
+        found: String,
+        expected: String,
+    },
+    /// A section header such as `States` or `Transitions` is missing.
+    MissingSection { section: &'static str },
+    /// The underlying parser rejected the input.
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The input does not contain any state.
+    Empty,
+    /// An error in one of several files that make up the input.
+    InFile {
+        file: &'static str,
+        error: Box<ParseError>,
+    },
+    /// The input could not be read.
+    Io { line: usize, message: String },
+    /// The input does not look like any known format.
+    UnknownFormat,
+}
+
+impl ParseError {
+    fn unexpected(line: usize, column: usize, found: &str, expected: &str) -> Self {
+        Self::Unexpected {
+            line,
+            column,
+            found: match found.is_empty() {
+                true => "end of line".to_string(),
+                false => found.to_string(),
+            },
+            expected: expected.to_string(),
+        }
+    }
+
+    fn end_of_input(line: usize, expected: &str) -> Self {
+        Self::Unexpected {
+            line,
+            column: 1,
+            found: "end of input".to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    fn in_file(self, file: &'static str) -> Self {
+        Self::InFile {
+            file,
+            error: Box::new(self),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Unexpected {
+                line,
+                column,
+                found,
+                expected,
+            } => write!(
+                f,
+                "line {}, column {}: expected {}, found `{}`",
+                line, column, expected, found
+            ),
+            ParseError::MissingSection { section } => {
+                write!(f, "missing `{}` section", section)
+            }
+            ParseError::Syntax {
+                line,
+                column,
+                message,
+            } => write!(f, "line {}, column {}: {}", line, column, message),
+            ParseError::Empty => write!(f, "no states found"),
+            ParseError::InFile { file, error } => write!(f, "in {} file, {}", file, error),
+            ParseError::Io { line, message } => write!(f, "line {}: {}", line, message),
+            ParseError::UnknownFormat => write!(f, "cannot detect the input format"),
+        }
+    }
 }
 
+impl std::error::Error for ParseError {}
+
+/// Calls `f` with every line of `reader` and its number, reusing one buffer.
+///
+/// Returns the number of lines read.
+fn for_each_line<R: BufRead>(
+    mut reader: R,
+    mut f: impl FnMut(usize, &str) -> Result<(), ParseError>,
+) -> Result<usize, ParseError> {
+    let mut line = String::new();
+    let mut line_no = 0;
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line).map_err(|e| ParseError::Io {
+            line: line_no + 1,
+            message: e.to_string(),
+        })?;
+        if read == 0 {
+            return Ok(line_no);
+        }
+        line_no += 1;
+        f(line_no, line.trim_end_matches(['\n', '\r']))?;
+    }
+}
+
+/// Column of `token` inside `line`, where `token` is a subslice of `line`.
+fn column_of(line: &str, token: &str) -> usize {
+    token.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+/// Parses an id token of `line`, reporting its position on failure.
+fn parse_id(line_no: usize, line: &str, token: &str, expected: &str) -> Result<usize, ParseError> {
+    let trimmed = token.trim();
+    trimmed
+        .parse::<usize>()
+        .map_err(|_| ParseError::unexpected(line_no, column_of(line, trimmed), trimmed, expected))
+}
+
+/// Splits `s` at every `separator` that is not enclosed in parentheses.
+fn split_top_level(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if c == separator && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Reads the replay of a pan error trail printed by
+/// `spin -t -p -g -l <model>`.
+///
+/// ```text
+/// using statement merging
+///   1:  proc  1 (P:1) model.pml:3 (state 1)    [flag = 1]
+///         P(1):flag = 1
+///   2:  proc  1 (P:1) model.pml:4 (state 2)    [turn = 1]
+///         turn = 1
+///   <<<<<START OF CYCLE>>>>>
+///   3:  proc  1 (P:1) model.pml:5 (state 3)    [turn = 0]
+///         turn = 0
+/// spin: trail ends after 3 steps
+/// ```
+///
+/// Every numbered step is a transition labelled with the executed statement,
+/// leading from the initial state to a new state. A state shows the global
+/// (`-g`) and local (`-l`) variables printed so far and the last statement
+/// location of each process. The step after `START OF CYCLE` closes the cycle,
+/// so the last step of an acceptance cycle leads back to where it started.
+/// Everything after `trail ends` and all other output, such as `printf`s of
+/// the model, is skipped.
+pub struct SPINTranslator;
+
 impl Translator for SPINTranslator {
-    type ErrorType = ();
-    fn translate(str: &str) -> Result<StateSpace, ()> {
-        todo!()
+    type ErrorType = ParseError;
+    fn translate(str: &str) -> Result<StateSpace, ParseError> {
+        Self::translate_reader(str.as_bytes())
     }
 }
 
+/// What has been replayed of a trail.
+#[derive(Default)]
+struct Trail {
+    /// Variables in the order they were first printed, with their last value.
+    vars: Vec<(String, String)>,
+    /// Processes in the order they first moved, with their last location.
+    procs: Vec<(String, String)>,
+    /// Number of the last step.
+    step: Option<usize>,
+    /// Statement of the last step, whose target state is still being printed.
+    pending: Option<String>,
+    /// Number of states emitted.
+    states: usize,
+    /// State the cycle of an acceptance cycle starts at.
+    cycle: Option<usize>,
+    ended: bool,
+}
+
+impl Trail {
+    /// Emits the state reached by the pending step together with the step.
+    fn flush(&mut self, emit: &mut impl FnMut(Event)) {
+        let Some(statement) = self.pending.take() else {
+            return;
+        };
+        let from = self.states - 1;
+        let info = self
+            .vars
+            .iter()
+            .map(|(name, value)| format!("{} = {}", name, value))
+            .chain(self.procs.iter().map(|(_, location)| location.clone()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        emit(Event::State(State::new(self.states, info)));
+        emit(Event::Transition(
+            Transition::new(from, self.states).with_label(statement),
+        ));
+        self.states += 1;
+    }
+
+    /// Ends the replay, leading the last step of a cycle back to its start.
+    fn finish(&mut self, emit: &mut impl FnMut(Event)) {
+        match (self.cycle, self.pending.take()) {
+            (Some(start), Some(statement)) => emit(Event::Transition(
+                Transition::new(self.states - 1, start).with_label(statement),
+            )),
+            (_, statement) => {
+                self.pending = statement;
+                self.flush(emit);
+            }
+        }
+        self.ended = true;
+    }
+}
+
+impl StreamTranslator for SPINTranslator {
+    fn translate_stream<R: BufRead>(
+        reader: R,
+        mut emit: impl FnMut(Event),
+    ) -> Result<(), ParseError> {
+        let mut trail = Trail::default();
+
+        for_each_line(reader, |line_no, line| {
+            let trimmed = line.trim();
+            if trail.ended {
+                return Ok(());
+            }
+            if trimmed.starts_with("spin: trail ends") {
+                trail.finish(&mut emit);
+                return Ok(());
+            }
+            if trimmed == "<<<<<START OF CYCLE>>>>>" {
+                trail.flush(&mut emit);
+                trail.cycle = Some(trail.states.saturating_sub(1));
+                return Ok(());
+            }
+
+            // `<step>: proc <pid> (<name>) <file>:<line> (state <n>) [<statement>]`
+            if let Some((step, rest)) = spin_step(trimmed) {
+                let (location, statement) = rest.split_once("(state ").ok_or_else(|| {
+                    ParseError::unexpected(line_no, column_of(line, rest), rest, "`(state <n>)`")
+                })?;
+                let (state, statement) = statement.split_once(')').ok_or_else(|| {
+                    ParseError::unexpected(line_no, column_of(line, statement), statement, "`)`")
+                })?;
+                parse_id(line_no, line, state, "process state")?;
+                // lines such as `proc 1 terminates` repeat the step number
+                if trail.step == Some(step) {
+                    return Ok(());
+                }
+                trail.step = Some(step);
+
+                if trail.states == 0 {
+                    emit(Event::State(State::new(0, "initial state".to_string())));
+                    emit(Event::Initial(0));
+                    trail.states = 1;
+                }
+                trail.flush(&mut emit);
+
+                let location = format!("{}(state {})", location, state.trim());
+                let pid = location.split_whitespace().nth(1).unwrap_or_default();
+                match trail.procs.iter_mut().find(|(known, _)| known == pid) {
+                    Some((_, known)) => *known = location,
+                    None => trail.procs.push((pid.to_string(), location)),
+                }
+                let statement = statement.trim();
+                let statement = statement
+                    .strip_prefix('[')
+                    .and_then(|statement| statement.strip_suffix(']'))
+                    .unwrap_or(statement);
+                trail.pending = Some(statement.to_string());
+                return Ok(());
+            }
+
+            // variables printed after the step that changed them
+            if trail.step.is_some() && line.starts_with(char::is_whitespace) {
+                if let Some((name, value)) = trimmed.split_once(" = ") {
+                    match trail.vars.iter_mut().find(|(known, _)| known == name) {
+                        Some((_, known)) => *known = value.to_string(),
+                        None => trail.vars.push((name.to_string(), value.to_string())),
+                    }
+                }
+            }
+            Ok(())
+        })?;
+
+        if !trail.ended {
+            trail.finish(&mut emit);
+        }
+        Ok(())
+    }
+}
+
+/// Splits a step line of a trail replay into its number and the text after
+/// `proc`.
+fn spin_step(line: &str) -> Option<(usize, &str)> {
+    let (step, rest) = line.split_once(':')?;
+    let step = step.parse().ok()?;
+    let rest = rest.trim_start();
+    rest.starts_with("proc ").then_some((step, rest))
+}
+
 pub struct SLIMTranslator {
     pub states: Vec<String>,
     pub transitions: Vec<String>,
 }
 
 impl Translator for SLIMTranslator {
-    type ErrorType = ();
-    fn translate(str: &str) -> Result<StateSpace, ()> {
-        let mut lines = str.lines().into_iter().peekable();
-        loop {
-            if let Some(line) = lines.next() {
-                if line.trim().starts_with("States") {
-                    break;
-                }
+    type ErrorType = ParseError;
+    fn translate(str: &str) -> Result<StateSpace, ParseError> {
+        Self::translate_reader(str.as_bytes())
+    }
+}
+
+/// Part of a slim dump that is currently being read.
+enum SlimSection {
+    /// Lines before the `States` header.
+    Preamble,
+    /// `<id>::<state>` lines after the header on line `header`.
+    States {
+        header: usize,
+    },
+    /// Lines before the `Transitions` header.
+    BeforeTransitions,
+    /// The line after the header on line `header`, which holds the initial
+    /// state if there is one.
+    Init {
+        header: usize,
+    },
+    Transitions,
+    /// Lines before the `Labels` header.
+    BeforeLabels,
+    Labels,
+    Done,
+}
+
+impl StreamTranslator for SLIMTranslator {
+    fn translate_stream<R: BufRead>(
+        reader: R,
+        mut emit: impl FnMut(Event),
+    ) -> Result<(), ParseError> {
+        let mut section = SlimSection::Preamble;
+        let lines = for_each_line(reader, |line_no, line| {
+            slim_line(&mut section, line_no, line, &mut emit)
+        })?;
+
+        match section {
+            SlimSection::Preamble => Err(ParseError::MissingSection { section: "States" }),
+            SlimSection::States { header } if header == lines => {
+                Err(ParseError::end_of_input(header + 1, "state"))
             }
+            SlimSection::States { .. } | SlimSection::BeforeTransitions => {
+                Err(ParseError::MissingSection {
+                    section: "Transitions",
+                })
+            }
+            SlimSection::Init { header } => {
+                Err(ParseError::end_of_input(header + 1, "initial state"))
+            }
+            _ => Ok(()),
         }
-        // check if there is a next line but don't consume it
-        if lines.peek().is_none() {
-            return Err(());
+    }
+}
+
+fn slim_line(
+    section: &mut SlimSection,
+    line_no: usize,
+    line: &str,
+    emit: &mut impl FnMut(Event),
+) -> Result<(), ParseError> {
+    match section {
+        SlimSection::Preamble => {
+            if line.trim().starts_with("States") {
+                *section = SlimSection::States { header: line_no };
+            }
         }
-        let mut nodes = vec![];
-        loop {
-            if let Some(line) = lines.next() {
-                let states = line.split("::").collect::<Vec<&str>>();
-                if states.len() < 2 {
-                    break;
-                }
-                let id = states[0].parse::<usize>().unwrap();
-                let state = states[1..].join("::");
-                nodes.push(State {
-                    index: id,
-                    info: state,
-                });
-            } else {
-                break;
-            }
-        }
-        loop {
-            if let Some(line) = lines.next() {
-                if line.trim().starts_with("Transitions") {
-                    break;
+        SlimSection::States { .. } => match line.split_once("::") {
+            Some((id, state)) => {
+                let id = parse_id(line_no, line, id, "state id")?;
+                let mut state = State::new(id, state.to_string());
+                // the raw text is kept for states that are not plain LMNtal
+                if let Ok(process) = lmntal::parse(&state.info) {
+                    state = state.with_lmntal(process);
                 }
+                emit(Event::State(state));
+            }
+            None => {
+                // the line ending the states may already be the next header
+                *section = SlimSection::BeforeTransitions;
+                return slim_line(section, line_no, line, emit);
+            }
+        },
+        SlimSection::BeforeTransitions => {
+            if line.trim().starts_with("Transitions") {
+                *section = SlimSection::Init { header: line_no };
             }
         }
-        if lines.next().is_none() {
-            return Err(());
+        SlimSection::Init { .. } => {
+            *section = SlimSection::Transitions;
+            let Some((_, id)) = line.split_once("init:") else {
+                // without an initial state the line is already a transition
+                return slim_line(section, line_no, line, emit);
+            };
+            emit(Event::Initial(parse_id(
+                line_no,
+                line,
+                id,
+                "initial state id",
+            )?));
         }
-        let mut edges = vec![];
-        loop {
-            if let Some(line) = lines.next() {
-                let states = line.split("::").collect::<Vec<&str>>();
-                if states.len() < 2 {
-                    break;
-                }
-                let from = states[0].parse::<usize>().unwrap();
-                let joins = states[1..].join("::");
-                let targets = joins.split(',').collect::<Vec<&str>>();
-                if (targets.len() == 1) && targets[0].is_empty() {
-                    continue;
-                }
-                for target in targets {
-                    let to = target.parse::<usize>().unwrap();
-                    edges.push(Transition { from, to });
+        SlimSection::Transitions => {
+            let Some((from, targets)) = line.split_once("::") else {
+                // the line ending the transitions may already be the next header
+                *section = SlimSection::BeforeLabels;
+                return slim_line(section, line_no, line, emit);
+            };
+            let from = parse_id(line_no, line, from, "state id")?;
+            if targets.is_empty() {
+                return Ok(());
+            }
+            // targets are `<id>` or, unless `--hide-ruleset` is passed,
+            // `<id>(<rule names>)`
+            for target in split_top_level(targets, ',') {
+                match target.split_once('(') {
+                    Some((to, rules)) => {
+                        let to = parse_id(line_no, line, to, "target state id")?;
+                        let rules = rules.trim_end().strip_suffix(')').ok_or_else(|| {
+                            ParseError::unexpected(line_no, column_of(line, rules), rules, "`)`")
+                        })?;
+                        emit(Event::Transition(
+                            Transition::new(from, to).with_label(rules.trim().to_string()),
+                        ));
+                    }
+                    None => {
+                        let to = parse_id(line_no, line, target, "target state id")?;
+                        emit(Event::Transition(Transition::new(from, to)));
+                    }
                 }
-            } else {
-                break;
             }
         }
-        Ok(StateSpace {
-            states: nodes,
-            transitions: edges,
-        })
+        // with `--ltl` the states of the property automaton follow as
+        // `<id>::<automaton state>`, accepting ones are named `accept...`
+        SlimSection::BeforeLabels => {
+            if line.trim().starts_with("Labels") {
+                *section = SlimSection::Labels;
+            }
+        }
+        SlimSection::Labels => {
+            let Some((id, name)) = line.split_once("::") else {
+                *section = SlimSection::Done;
+                return Ok(());
+            };
+            let id = parse_id(line_no, line, id, "state id")?;
+            if name.trim().starts_with("accept") {
+                emit(Event::Accepting(id));
+            }
+        }
+        SlimSection::Done => {}
+    }
+    Ok(())
+}
+
+/// Reads a Kripke structure from JSON.
+///
+/// ```json
+/// {
+///     "states": [
+///         { "id": 0, "name": "idle", "props": ["ready"] },
+///         { "id": 1, "props": ["busy"] }
+///     ],
+///     "initial": [0],
+///     "accepting": [1],
+///     "transitions": [
+///         { "from": 0, "to": 1 },
+///         { "from": 1, "to": 0 }
+///     ]
+/// }
+/// ```
+///
+/// `name`, `props`, `initial` and `accepting` are optional. A state may give
+/// its `info` verbatim instead of a name, and transitions may carry a `label`
+/// and a `weight`, as in the files written by the JSON export.
+pub struct KripkeJsonTranslator;
+
+#[derive(Deserialize)]
+struct KripkeJson {
+    states: Vec<KripkeJsonState>,
+    #[serde(default)]
+    initial: Vec<usize>,
+    #[serde(default)]
+    accepting: Vec<usize>,
+    #[serde(default)]
+    transitions: Vec<KripkeJsonTransition>,
+}
+
+#[derive(Deserialize)]
+struct KripkeJsonState {
+    id: usize,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    info: Option<String>,
+    #[serde(default)]
+    props: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct KripkeJsonTransition {
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    weight: Option<f64>,
+}
+
+impl Translator for KripkeJsonTranslator {
+    type ErrorType = ParseError;
+    fn translate(str: &str) -> Result<StateSpace, ParseError> {
+        let kripke: KripkeJson = serde_json::from_str(str).map_err(|e| {
+            // serde_json appends the position, which is reported separately
+            let position = format!(" at line {} column {}", e.line(), e.column());
+            ParseError::Syntax {
+                line: e.line(),
+                column: e.column(),
+                message: e.to_string().trim_end_matches(&position).to_string(),
+            }
+        })?;
+        if kripke.states.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let nodes = kripke
+            .states
+            .into_iter()
+            .map(|state| {
+                let info = match (state.info, state.name) {
+                    (Some(info), _) => info,
+                    (None, Some(name)) => format!("{} {{{}}}", name, state.props.join(", ")),
+                    (None, None) => format!("{{{}}}", state.props.join(", ")),
+                };
+                State::new(state.id, info).with_props(state.props)
+            })
+            .collect();
+
+        let edges = kripke
+            .transitions
+            .into_iter()
+            .map(|transition| {
+                let mut edge = Transition::new(transition.from, transition.to);
+                edge.label = transition.label;
+                edge.weight = transition.weight;
+                edge
+            })
+            .collect();
+
+        Ok(StateSpace::new(nodes, edges)
+            .with_initial(kripke.initial)
+            .with_accepting(kripke.accepting))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syntax_error(input: &str) -> String {
+        match KripkeJsonTranslator::translate(input) {
+            Err(ParseError::Syntax { message, .. }) => message,
+            other => panic!("expected a syntax error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    const TRAIL: &str = "using statement merging
+  1:\tproc  1 (P:1) model.pml:3 (state 1)\t[flag = 1]
+\t\tP(1):flag = 1
+hello from P
+  2:\tproc  0 (:init::1) model.pml:9 (state 4)\t[a[0] = 2]
+\t\ta[0] = 2
+  <<<<<START OF CYCLE>>>>>
+  3:\tproc  1 (P:1) model.pml:4 (state 2)\t[flag = 0]
+\t\tP(1):flag = 0
+  4:\tproc  1 (P:1) model.pml:5 (state 3)\t[flag = 1]
+\t\tP(1):flag = 1
+spin: trail ends after 4 steps
+#processes: 2
+\t\ta[0] = 2
+  4:\tproc  1 (P:1) model.pml:6 (state 4)
+  4:\tproc  0 (:init::1) model.pml:10 (state 5) <valid end state>
+2 processes created
+";
+
+    fn spin(input: &str) -> Result<StateSpace, ParseError> {
+        SPINTranslator::translate(input)
+    }
+
+    #[test]
+    fn spin_trail() {
+        let space = spin(TRAIL).unwrap();
+        assert_eq!(space.states.len(), 4);
+        assert_eq!(space.initial, [0].into());
+        // the last step of the cycle leads back to where it started
+        assert_eq!(edges(&space), [(0, 1), (1, 2), (2, 3), (3, 2)]);
+        let labels = space
+            .transitions
+            .iter()
+            .map(|t| t.label.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, ["flag = 1", "a[0] = 2", "flag = 0", "flag = 1"]);
+        assert_eq!(
+            space.states[2].info,
+            "P(1):flag = 1\na[0] = 2\nproc  1 (P:1) model.pml:3 (state 1)\nproc  0 (:init::1) model.pml:9 (state 4)"
+        );
+    }
+
+    #[test]
+    fn spin_trail_without_cycle() {
+        let trail = TRAIL.replace("  <<<<<START OF CYCLE>>>>>\n", "");
+        let space = spin(&trail).unwrap();
+        assert_eq!(space.states.len(), 5);
+        assert_eq!(edges(&space), [(0, 1), (1, 2), (2, 3), (3, 4)]);
+        assert!(space.states[4].info.starts_with("P(1):flag = 1\n"));
+    }
+
+    #[test]
+    fn spin_errors() {
+        assert_eq!(
+            spin("using statement merging\n").unwrap_err(),
+            ParseError::Empty
+        );
+        assert_eq!(
+            spin("  1:\tproc  0 (:init::1) model.pml:3 [x = 1]\n").unwrap_err(),
+            ParseError::unexpected(
+                1,
+                6,
+                "proc  0 (:init::1) model.pml:3 [x = 1]",
+                "`(state <n>)`"
+            )
+        );
+        assert_eq!(
+            spin("  1:\tproc  0 (:init::1) model.pml:3 (state x)\n").unwrap_err(),
+            ParseError::unexpected(1, 44, "x", "process state")
+        );
+    }
+
+    const SLIM: &str = "States\n0::a.\n1::b.\n\nTransitions\ninit:0\n0::1\n1::0(r)\n";
+
+    fn slim(input: &str) -> Result<StateSpace, ParseError> {
+        SLIMTranslator::translate(input)
+    }
+
+    fn edges(space: &StateSpace) -> Vec<(usize, usize)> {
+        space.transitions.iter().map(|t| (t.from, t.to)).collect()
+    }
+
+    #[test]
+    fn slim_dump() {
+        let space = slim(SLIM).unwrap();
+        assert_eq!(space.states.len(), 2);
+        assert_eq!(space.initial, [0].into());
+        assert_eq!(edges(&space), [(0, 1), (1, 0)]);
+        assert_eq!(space.transitions[1].label.as_deref(), Some("r"));
+    }
+
+    #[test]
+    fn slim_transitions_without_initial_state() {
+        let space = slim("States\n0::a.\n1::b.\n\nTransitions\n0::1\n1::0\n").unwrap();
+        assert!(space.initial.is_empty());
+        assert_eq!(edges(&space), [(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn slim_missing_sections() {
+        assert_eq!(
+            slim("0::a.\n").unwrap_err(),
+            ParseError::MissingSection { section: "States" }
+        );
+        assert_eq!(
+            slim("States\n0::a.\n").unwrap_err(),
+            ParseError::MissingSection {
+                section: "Transitions"
+            }
+        );
+    }
+
+    #[test]
+    fn slim_bad_ids() {
+        assert_eq!(
+            slim("States\nx::a.\n").unwrap_err(),
+            ParseError::unexpected(2, 1, "x", "state id")
+        );
+        assert_eq!(
+            slim("States\n0::a.\n\nTransitions\ninit:zero\n").unwrap_err(),
+            ParseError::unexpected(5, 6, "zero", "initial state id")
+        );
+        assert_eq!(
+            slim("States\n0::a.\n\nTransitions\ninit:0\n0::1,y\n").unwrap_err(),
+            ParseError::unexpected(6, 6, "y", "target state id")
+        );
+        assert_eq!(
+            slim("States\n0::a.\n\nTransitions\ninit:0\n0::1(r\n").unwrap_err(),
+            ParseError::unexpected(6, 6, "r", "`)`")
+        );
+    }
+
+    #[test]
+    fn slim_truncated() {
+        assert_eq!(
+            slim("States\n").unwrap_err(),
+            ParseError::end_of_input(2, "state")
+        );
+        assert_eq!(
+            slim("States\n0::a.\n\nTransitions\n").unwrap_err(),
+            ParseError::end_of_input(5, "initial state")
+        );
+        // a dump cut off within the transitions keeps what was read
+        let space = slim(&SLIM[..SLIM.len() - "1::0(r)\n".len()]).unwrap();
+        assert_eq!(edges(&space), [(0, 1)]);
+    }
+
+    #[test]
+    fn kripke_document() {
+        let space = KripkeJsonTranslator::translate(
+            r#"{
+                "states": [
+                    { "id": 0, "name": "idle", "props": ["ready"] },
+                    { "id": 1, "props": ["busy", "locked"] },
+                    { "id": 2, "info": "x = 2" }
+                ],
+                "initial": [0],
+                "accepting": [1],
+                "transitions": [
+                    { "from": 0, "to": 1, "label": "go", "weight": 0.5 },
+                    { "from": 1, "to": 0 }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let infos = space
+            .states
+            .iter()
+            .map(|s| s.info.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(infos, ["idle {ready}", "{busy, locked}", "x = 2"]);
+        assert_eq!(space.states[1].props, ["busy", "locked"]);
+        assert_eq!(space.initial, [0].into());
+        assert_eq!(space.accepting, [1].into());
+        let go = &space.transitions[0];
+        assert_eq!((go.from, go.to), (0, 1));
+        assert_eq!(go.label.as_deref(), Some("go"));
+        assert_eq!(go.weight, Some(0.5));
+        assert_eq!(space.transitions[1].label, None);
+    }
+
+    #[test]
+    fn kripke_without_states() {
+        assert_eq!(
+            KripkeJsonTranslator::translate(r#"{ "states": [] }"#).unwrap_err(),
+            ParseError::Empty
+        );
+        assert!(syntax_error(r#"{ "transitions": [] }"#).contains("missing field `states`"));
+        assert!(syntax_error(r#"{ "states": {} }"#).contains("expected a sequence"));
+    }
+
+    #[test]
+    fn kripke_rejected_states_and_transitions() {
+        assert!(syntax_error(r#"{ "states": [{ "name": "a" }] }"#).contains("missing field `id`"));
+        assert!(syntax_error(r#"{ "states": [{ "id": -1 }] }"#).contains("invalid value"));
+        assert!(syntax_error(r#"{ "states": [{ "id": 0, "props": "p" }] }"#)
+            .contains("expected a sequence"));
+        assert!(
+            syntax_error(r#"{ "states": [{ "id": 0 }], "transitions": [{ "from": 0 }] }"#)
+                .contains("missing field `to`")
+        );
+    }
+
+    #[test]
+    fn kripke_syntax_error_position() {
+        let error = KripkeJsonTranslator::translate("{\n  \"states\": [\n    { \"id\": 0 ]\n}")
+            .unwrap_err();
+        assert!(
+            matches!(error, ParseError::Syntax { line: 3, .. }),
+            "{:?}",
+            error
+        );
+        assert!(!error.to_string().contains(" at line "));
     }
 }
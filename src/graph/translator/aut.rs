@@ -0,0 +1,258 @@
+use std::{collections::HashSet, io::BufRead};
+
+use crate::graph::{state::State, transition::Transition, StateSpace};
+
+use super::{column_of, for_each_line, parse_id, Event, ParseError, StreamTranslator, Translator};
+
+/// Reads labelled transition systems in the Aldebaran (`.aut`) format used by
+/// mCRL2 and CADP.
+///
+/// ```text
+/// des (0, 3, 3)
+/// (0, "lock", 1)
+/// (1, "unlock", 0)
+/// (1, tau, 2)
+/// ```
+///
+/// The header declares the initial state, the number of transitions and the
+/// number of states. States are numbered from `0` to `nstates - 1`; only the
+/// initial state and the states taking part in a transition are created, so
+/// isolated states declared by the header are left out.
+pub struct AutTranslator;
+
+impl Translator for AutTranslator {
+    type ErrorType = ParseError;
+    fn translate(str: &str) -> Result<StateSpace, ParseError> {
+        Self::translate_reader(str.as_bytes())
+    }
+}
+
+/// The `des` header of an `.aut` file.
+struct Header {
+    line: usize,
+    init: usize,
+    ntrans: usize,
+    nstates: usize,
+    /// Column of the number of transitions, for reporting a wrong count.
+    ntrans_column: usize,
+}
+
+impl StreamTranslator for AutTranslator {
+    fn translate_stream<R: BufRead>(
+        reader: R,
+        mut emit: impl FnMut(Event),
+    ) -> Result<(), ParseError> {
+        let mut header: Option<Header> = None;
+        let mut count = 0;
+        // states are created when first seen, not from the declared count
+        let mut seen = HashSet::new();
+
+        for_each_line(reader, |line_no, line| {
+            if line.trim().is_empty() {
+                return Ok(());
+            }
+            match &header {
+                None => {
+                    let parsed = parse_header(line_no, line)?;
+                    seen.insert(parsed.init);
+                    emit(Event::State(State::new(
+                        parsed.init,
+                        parsed.init.to_string(),
+                    )));
+                    emit(Event::Initial(parsed.init));
+                    header = Some(parsed);
+                }
+                Some(header) => {
+                    let transition = parse_transition(line_no, line, header.nstates)?;
+                    for id in [transition.from, transition.to] {
+                        if seen.insert(id) {
+                            emit(Event::State(State::new(id, id.to_string())));
+                        }
+                    }
+                    emit(Event::Transition(transition));
+                    count += 1;
+                }
+            }
+            Ok(())
+        })?;
+
+        let Some(header) = header else {
+            return Err(ParseError::Empty);
+        };
+        if count != header.ntrans {
+            return Err(ParseError::Syntax {
+                line: header.line,
+                column: header.ntrans_column,
+                message: format!(
+                    "header declares {} transitions, found {}",
+                    header.ntrans, count
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Parses the `des (<init>, <ntrans>, <nstates>)` header.
+fn parse_header(line_no: usize, header: &str) -> Result<Header, ParseError> {
+    let malformed = || {
+        ParseError::unexpected(
+            line_no,
+            column_of(header, header.trim()),
+            header.trim(),
+            "`des (<init>, <ntrans>, <nstates>)`",
+        )
+    };
+    let fields = header
+        .trim()
+        .strip_prefix("des")
+        .and_then(|rest| parenthesized(rest.trim()))
+        .ok_or_else(malformed)?;
+    let fields = fields.split(',').collect::<Vec<_>>();
+    if fields.len() != 3 {
+        return Err(malformed());
+    }
+    let init = parse_id(line_no, header, fields[0], "initial state")?;
+    let ntrans = parse_id(line_no, header, fields[1], "number of transitions")?;
+    let nstates = parse_id(line_no, header, fields[2], "number of states")?;
+    if nstates == 0 {
+        return Err(ParseError::Empty);
+    }
+    if init >= nstates {
+        return Err(ParseError::unexpected(
+            line_no,
+            column_of(header, fields[0].trim()),
+            fields[0].trim(),
+            "initial state below the number of states",
+        ));
+    }
+
+    Ok(Header {
+        line: line_no,
+        init,
+        ntrans,
+        nstates,
+        ntrans_column: column_of(header, fields[1].trim()),
+    })
+}
+
+fn parse_transition(line_no: usize, line: &str, nstates: usize) -> Result<Transition, ParseError> {
+    let trimmed = line.trim();
+    let malformed = || {
+        ParseError::unexpected(
+            line_no,
+            column_of(line, trimmed),
+            trimmed,
+            "`(<from>, <label>, <to>)`",
+        )
+    };
+    let inner = parenthesized(trimmed).ok_or_else(malformed)?;
+    // the label may contain commas, so split at the outermost ones
+    let (from, rest) = inner.split_once(',').ok_or_else(malformed)?;
+    let (label, to) = rest.rsplit_once(',').ok_or_else(malformed)?;
+
+    let (from_token, to_token) = (from.trim(), to.trim());
+    let from = parse_id(line_no, line, from, "source state")?;
+    let to = parse_id(line_no, line, to, "target state")?;
+    for (id, token) in [(from, from_token), (to, to_token)] {
+        if id >= nstates {
+            return Err(ParseError::unexpected(
+                line_no,
+                column_of(line, token),
+                token,
+                "state below the number of states",
+            ));
+        }
+    }
+
+    let label = label.trim();
+    let label = label
+        .strip_prefix('"')
+        .and_then(|label| label.strip_suffix('"'))
+        .unwrap_or(label);
+    Ok(Transition::new(from, to).with_label(label.to_string()))
+}
+
+/// Content between the enclosing parentheses of `s`.
+fn parenthesized(s: &str) -> Option<&str> {
+    s.strip_prefix('(')?.strip_suffix(')')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aut(input: &str) -> Result<StateSpace, ParseError> {
+        AutTranslator::translate(input)
+    }
+
+    #[test]
+    fn header_and_transitions() {
+        let space = aut("des (1, 2, 3)\n(1, \"lock\", 0)\n(0, tau, 1)\n").unwrap();
+        let mut states = space.states.iter().map(|s| s.index).collect::<Vec<_>>();
+        states.sort();
+        assert_eq!(states, [0, 1]);
+        assert_eq!(space.initial, [1].into());
+        let labels = space
+            .transitions
+            .iter()
+            .map(|t| t.label.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, ["lock", "tau"]);
+    }
+
+    #[test]
+    fn quoted_label_with_a_comma() {
+        let space = aut("des (0, 1, 2)\n(0, \"send(a, b)\", 1)\n").unwrap();
+        let transition = &space.transitions[0];
+        assert_eq!((transition.from, transition.to), (0, 1));
+        assert_eq!(transition.label.as_deref(), Some("send(a, b)"));
+    }
+
+    #[test]
+    fn large_state_count_is_not_allocated() {
+        let space = aut("des (0, 0, 4000000000)\n").unwrap();
+        assert_eq!(space.states.len(), 1);
+    }
+
+    #[test]
+    fn malformed_header() {
+        assert_eq!(
+            aut("des 0, 0, 1\n").unwrap_err(),
+            ParseError::unexpected(1, 1, "des 0, 0, 1", "`des (<init>, <ntrans>, <nstates>)`")
+        );
+        assert_eq!(
+            aut("des (0, 0)\n").unwrap_err(),
+            ParseError::unexpected(1, 1, "des (0, 0)", "`des (<init>, <ntrans>, <nstates>)`")
+        );
+        assert_eq!(aut("des (0, 0, 0)\n").unwrap_err(), ParseError::Empty);
+    }
+
+    #[test]
+    fn initial_state_out_of_range() {
+        assert_eq!(
+            aut("des (2, 0, 2)\n").unwrap_err(),
+            ParseError::unexpected(1, 6, "2", "initial state below the number of states")
+        );
+    }
+
+    #[test]
+    fn state_out_of_range() {
+        assert_eq!(
+            aut("des (0, 1, 2)\n(0, a, 2)\n").unwrap_err(),
+            ParseError::unexpected(2, 8, "2", "state below the number of states")
+        );
+    }
+
+    #[test]
+    fn wrong_transition_count() {
+        assert_eq!(
+            aut("des (0, 2, 2)\n(0, a, 1)\n").unwrap_err(),
+            ParseError::Syntax {
+                line: 1,
+                column: 9,
+                message: "header declares 2 transitions, found 1".to_string(),
+            }
+        );
+    }
+}
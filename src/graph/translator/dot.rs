@@ -0,0 +1,683 @@
+use std::collections::HashMap;
+
+use crate::graph::{state::State, transition::Transition, StateSpace};
+
+use super::{ParseError, Translator};
+
+/// Reads state graphs written in the Graphviz DOT language, as produced by
+/// TLC (`-dump dot`), slim and many other tools.
+///
+/// Every node becomes a state and every edge a transition. The `label`
+/// attribute of a node is used as the state info (falling back to the node
+/// id), and the `label` and `weight` attributes of an edge become the label
+/// and weight of the transition.
+/// Nodes are numbered in the order they first appear. Subgraphs are
+/// flattened, and an edge to a subgraph connects to every node inside it.
+/// Defaults set with `node [..]` and `edge [..]` apply to the nodes and edges
+/// that follow in the same graph or subgraph.
+///
+/// Initial and accepting states follow the usual drawing conventions:
+/// * the targets of an edge from a `point` node are initial, as is the target
+///   of a `none` or `plaintext` node with `label=""` whose only edge leads to it;
+///   such start nodes are dropped with all their edges,
+/// * nodes with `shape=doublecircle` are accepting.
+pub struct DotTranslator;
+
+impl Translator for DotTranslator {
+    type ErrorType = ParseError;
+    fn translate(str: &str) -> Result<StateSpace, ParseError> {
+        let tokens = lex(str)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            nodes: vec![],
+            node_map: HashMap::new(),
+            edges: vec![],
+            node_defaults: HashMap::new(),
+            edge_defaults: HashMap::new(),
+        };
+        parser.graph()?;
+
+        let attr = |idx: usize, key: &str| parser.nodes[idx].1.get(key).map(String::as_str);
+        let mut degree = vec![0; parser.nodes.len()];
+        for (from, to, _) in &parser.edges {
+            degree[*from] += 1;
+            degree[*to] += 1;
+        }
+        let start = (0..parser.nodes.len())
+            .map(|idx| match attr(idx, "shape") {
+                Some("point") => true,
+                Some("none" | "plaintext") => {
+                    // without a label Graphviz shows the node id
+                    attr(idx, "label") == Some("")
+                        && degree[idx] == 1
+                        && parser
+                            .edges
+                            .iter()
+                            .any(|(from, to, _)| *from == idx && *to != idx)
+                }
+                _ => false,
+            })
+            .collect::<Vec<_>>();
+
+        let mut initial = vec![];
+        let mut accepting = vec![];
+        let mut nodes = vec![];
+        for (index, (id, attrs)) in parser.nodes.iter().enumerate() {
+            if start[index] {
+                continue;
+            }
+            if attrs
+                .get("shape")
+                .is_some_and(|shape| shape == "doublecircle")
+            {
+                accepting.push(index);
+            }
+            let info = match attrs.get("label") {
+                Some(label) => unescape(label),
+                None => id.clone(),
+            };
+            nodes.push(State::new(index, info));
+        }
+        if nodes.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let mut edges = vec![];
+        for (from, to, attrs) in parser.edges {
+            if start[from] && !start[to] {
+                initial.push(to);
+            }
+            if start[from] || start[to] {
+                continue;
+            }
+            let mut transition = Transition::new(from, to);
+            if let Some(label) = attrs.get("label") {
+                transition = transition.with_label(unescape(label));
+            }
+            if let Some(weight) = attrs.get("weight").and_then(|w| w.parse().ok()) {
+                transition = transition.with_weight(weight);
+            }
+            edges.push(transition);
+        }
+
+        Ok(StateSpace::new(nodes, edges)
+            .with_initial(initial)
+            .with_accepting(accepting))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Identifier, numeral, quoted or HTML string.
+    Id(String),
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Equal,
+    Semicolon,
+    Comma,
+    Colon,
+    /// `->` or `--`
+    EdgeOp,
+}
+
+impl Token {
+    fn text(&self) -> String {
+        match self {
+            Token::Id(id) => id.clone(),
+            Token::LBrace => "{".to_string(),
+            Token::RBrace => "}".to_string(),
+            Token::LBracket => "[".to_string(),
+            Token::RBracket => "]".to_string(),
+            Token::Equal => "=".to_string(),
+            Token::Semicolon => ";".to_string(),
+            Token::Comma => ",".to_string(),
+            Token::Colon => ":".to_string(),
+            Token::EdgeOp => "->".to_string(),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Id(id) if id.eq_ignore_ascii_case(keyword))
+    }
+}
+
+/// A token together with its 1-based line and column.
+type Spanned = (Token, usize, usize);
+
+/// Character cursor that keeps track of the current position.
+struct Cursor<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+    /// Whether only whitespace has been seen on the current line.
+    line_start: bool,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        let mut ahead = self.chars.clone();
+        ahead.next();
+        ahead.next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        match c {
+            Some('\n') => {
+                self.line += 1;
+                self.column = 1;
+                self.line_start = true;
+            }
+            Some(c) => {
+                self.column += 1;
+                if !c.is_whitespace() {
+                    self.line_start = false;
+                }
+            }
+            None => {}
+        }
+        c
+    }
+
+    fn skip_line(&mut self) {
+        while !matches!(self.peek(), Some('\n') | None) {
+            self.bump();
+        }
+    }
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.' || c == '-'
+}
+
+fn lex(str: &str) -> Result<Vec<Spanned>, ParseError> {
+    let mut tokens = vec![];
+    let mut cursor = Cursor {
+        chars: str.chars().peekable(),
+        line: 1,
+        column: 1,
+        line_start: true,
+    };
+
+    while let Some(c) = cursor.peek() {
+        let (line, column) = (cursor.line, cursor.column);
+        match c {
+            c if c.is_whitespace() => {
+                cursor.bump();
+            }
+            // preprocessor output lines
+            '#' if cursor.line_start => cursor.skip_line(),
+            '/' => {
+                cursor.bump();
+                match cursor.bump() {
+                    Some('/') => cursor.skip_line(),
+                    Some('*') => {
+                        let mut prev = ' ';
+                        loop {
+                            match cursor.bump() {
+                                Some('/') if prev == '*' => break,
+                                Some(c) => prev = c,
+                                None => return Err(ParseError::end_of_input(cursor.line, "`*/`")),
+                            }
+                        }
+                    }
+                    _ => return Err(ParseError::unexpected(line, column, "/", "`//` or `/*`")),
+                }
+            }
+            '"' => {
+                cursor.bump();
+                let mut id = String::new();
+                loop {
+                    match cursor.bump() {
+                        Some('\\') if cursor.peek() == Some('"') => {
+                            cursor.bump();
+                            id.push('"');
+                        }
+                        // kept for `unescape`, so that `\\"` does not escape the quote
+                        Some('\\') if cursor.peek() == Some('\\') => {
+                            cursor.bump();
+                            id.push_str("\\\\");
+                        }
+                        // line continuation
+                        Some('\\') if cursor.peek() == Some('\n') => {
+                            cursor.bump();
+                        }
+                        Some('"') => break,
+                        Some(c) => id.push(c),
+                        None => return Err(ParseError::end_of_input(cursor.line, "closing `\"`")),
+                    }
+                }
+                tokens.push((Token::Id(id), line, column));
+            }
+            '<' => {
+                cursor.bump();
+                let mut id = String::new();
+                let mut depth = 1;
+                loop {
+                    match cursor.bump() {
+                        Some('>') if depth == 1 => break,
+                        Some(c) => {
+                            match c {
+                                '<' => depth += 1,
+                                '>' => depth -= 1,
+                                _ => {}
+                            }
+                            id.push(c);
+                        }
+                        None => return Err(ParseError::end_of_input(cursor.line, "closing `>`")),
+                    }
+                }
+                tokens.push((Token::Id(id), line, column));
+            }
+            '-' if matches!(cursor.peek_second(), Some('>') | Some('-')) => {
+                cursor.bump();
+                cursor.bump();
+                tokens.push((Token::EdgeOp, line, column));
+            }
+            '{' | '}' | '[' | ']' | '=' | ';' | ',' | ':' => {
+                cursor.bump();
+                let token = match c {
+                    '{' => Token::LBrace,
+                    '}' => Token::RBrace,
+                    '[' => Token::LBracket,
+                    ']' => Token::RBracket,
+                    '=' => Token::Equal,
+                    ';' => Token::Semicolon,
+                    ',' => Token::Comma,
+                    _ => Token::Colon,
+                };
+                tokens.push((token, line, column));
+            }
+            c if is_id_char(c) => {
+                let mut id = String::new();
+                while let Some(c) = cursor.peek() {
+                    // an edge operator ends the identifier
+                    let edge_op = c == '-' && matches!(cursor.peek_second(), Some('>') | Some('-'));
+                    if !is_id_char(c) || edge_op {
+                        break;
+                    }
+                    id.push(c);
+                    cursor.bump();
+                }
+                tokens.push((Token::Id(id), line, column));
+            }
+            c => {
+                return Err(ParseError::unexpected(
+                    line,
+                    column,
+                    &c.to_string(),
+                    "identifier, string or punctuation",
+                ))
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    pos: usize,
+    /// Node ids in order of appearance together with their attributes.
+    nodes: Vec<(String, HashMap<String, String>)>,
+    node_map: HashMap<String, usize>,
+    edges: Vec<(usize, usize, HashMap<String, String>)>,
+    /// Attributes set by `node [..]` and `edge [..]` in the current scope.
+    node_defaults: HashMap<String, String>,
+    edge_defaults: HashMap<String, String>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _, _)| token)
+    }
+
+    fn error(&self, expected: &str) -> ParseError {
+        match self.tokens.get(self.pos) {
+            Some((token, line, column)) => {
+                ParseError::unexpected(*line, *column, &token.text(), expected)
+            }
+            None => {
+                let line = self.tokens.last().map(|(_, line, _)| *line).unwrap_or(1);
+                ParseError::end_of_input(line, expected)
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
+        if self.peek() != Some(&expected) {
+            return Err(self.error(&format!("`{}`", expected.text())));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn id(&mut self, expected: &str) -> Result<String, ParseError> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                let id = id.clone();
+                self.pos += 1;
+                Ok(id)
+            }
+            _ => Err(self.error(expected)),
+        }
+    }
+
+    /// `[strict] (graph | digraph) [ID] '{' stmt_list '}'`
+    fn graph(&mut self) -> Result<(), ParseError> {
+        if self.peek().is_some_and(|t| t.is_keyword("strict")) {
+            self.pos += 1;
+        }
+        match self.peek() {
+            Some(t) if t.is_keyword("graph") || t.is_keyword("digraph") => self.pos += 1,
+            _ => return Err(self.error("`graph` or `digraph`")),
+        }
+        if let Some(Token::Id(_)) = self.peek() {
+            self.pos += 1;
+        }
+        self.expect(Token::LBrace)?;
+        self.stmt_list()?;
+        self.expect(Token::RBrace)?;
+        if self.peek().is_some() {
+            return Err(self.error("end of input"));
+        }
+        Ok(())
+    }
+
+    /// Parses statements up to the closing brace and returns the nodes they mention.
+    fn stmt_list(&mut self) -> Result<Vec<usize>, ParseError> {
+        let mut mentioned = vec![];
+        while !matches!(self.peek(), Some(Token::RBrace) | None) {
+            mentioned.extend(self.stmt()?);
+            if self.peek() == Some(&Token::Semicolon) {
+                self.pos += 1;
+            }
+        }
+        Ok(mentioned)
+    }
+
+    fn stmt(&mut self) -> Result<Vec<usize>, ParseError> {
+        // attribute statements set the defaults of the following nodes and edges
+        if let Some(kind) = self.peek().and_then(|t| {
+            ["graph", "node", "edge"]
+                .into_iter()
+                .find(|keyword| t.is_keyword(keyword))
+        }) {
+            self.pos += 1;
+            let attrs = self.attr_list()?;
+            match kind {
+                "node" => self.node_defaults.extend(attrs),
+                "edge" => self.edge_defaults.extend(attrs),
+                _ => {}
+            }
+            return Ok(vec![]);
+        }
+        // `ID = ID` sets a graph attribute
+        if let (Some(Token::Id(_)), Some((Token::Equal, _, _))) =
+            (self.peek(), self.tokens.get(self.pos + 1))
+        {
+            self.pos += 2;
+            self.id("attribute value")?;
+            return Ok(vec![]);
+        }
+
+        let mut sources = self.endpoint()?;
+        let mut mentioned = sources.clone();
+        if self.peek() != Some(&Token::EdgeOp) {
+            // node statement, unless it was a subgraph
+            let attrs = self.attr_list()?;
+            for idx in &sources {
+                self.nodes[*idx].1.extend(attrs.clone());
+            }
+            return Ok(mentioned);
+        }
+
+        let mut pairs = vec![];
+        while self.peek() == Some(&Token::EdgeOp) {
+            self.pos += 1;
+            let targets = self.endpoint()?;
+            for from in &sources {
+                for to in &targets {
+                    pairs.push((*from, *to));
+                }
+            }
+            mentioned.extend(targets.iter().copied());
+            sources = targets;
+        }
+        let mut attrs = self.edge_defaults.clone();
+        attrs.extend(self.attr_list()?);
+        self.edges.extend(
+            pairs
+                .into_iter()
+                .map(|(from, to)| (from, to, attrs.clone())),
+        );
+        Ok(mentioned)
+    }
+
+    /// A node id with an optional port, or a subgraph.
+    fn endpoint(&mut self) -> Result<Vec<usize>, ParseError> {
+        if self.peek().is_some_and(|t| t.is_keyword("subgraph")) {
+            self.pos += 1;
+            if let Some(Token::Id(_)) = self.peek() {
+                self.pos += 1;
+            }
+        }
+        if self.peek() == Some(&Token::LBrace) {
+            self.pos += 1;
+            // defaults set inside a subgraph end with it
+            let defaults = (self.node_defaults.clone(), self.edge_defaults.clone());
+            let mentioned = self.stmt_list()?;
+            (self.node_defaults, self.edge_defaults) = defaults;
+            self.expect(Token::RBrace)?;
+            return Ok(mentioned);
+        }
+
+        let id = self.id("node id or subgraph")?;
+        // ports are irrelevant for the state space
+        for _ in 0..2 {
+            if self.peek() != Some(&Token::Colon) {
+                break;
+            }
+            self.pos += 1;
+            self.id("port")?;
+        }
+        Ok(vec![self.node(id)])
+    }
+
+    fn node(&mut self, id: String) -> usize {
+        if let Some(idx) = self.node_map.get(&id) {
+            return *idx;
+        }
+        let idx = self.nodes.len();
+        self.node_map.insert(id.clone(), idx);
+        self.nodes.push((id, self.node_defaults.clone()));
+        idx
+    }
+
+    /// `('[' [ID '=' ID [(';' | ',')]]* ']')*`
+    fn attr_list(&mut self) -> Result<HashMap<String, String>, ParseError> {
+        let mut attrs = HashMap::new();
+        while self.peek() == Some(&Token::LBracket) {
+            self.pos += 1;
+            while self.peek() != Some(&Token::RBracket) {
+                let key = self.id("attribute name or `]`")?;
+                self.expect(Token::Equal)?;
+                let value = self.id("attribute value")?;
+                attrs.insert(key, value);
+                if matches!(self.peek(), Some(Token::Comma) | Some(Token::Semicolon)) {
+                    self.pos += 1;
+                }
+            }
+            self.pos += 1;
+        }
+        Ok(attrs)
+    }
+}
+
+/// Resolves the escape sequences of DOT labels, turning line breaks into newlines.
+fn unescape(label: &str) -> String {
+    let mut res = String::with_capacity(label.len());
+    let mut chars = label.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('l') | Some('r') => res.push('\n'),
+            Some('\\') => res.push('\\'),
+            Some(c) => {
+                res.push('\\');
+                res.push(c);
+            }
+            None => res.push('\\'),
+        }
+    }
+    res.trim_end_matches('\n').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use egui_graphs::Graph;
+    use petgraph::Directed;
+
+    use super::*;
+    use crate::{export::dot::to_dot, settings::SettingsStyle};
+
+    #[test]
+    fn backslash_before_closing_quote() {
+        let space = DotTranslator::translate(r#"digraph { 0 [label="C:\\"]; }"#).unwrap();
+        assert_eq!(space.states[0].info, "C:\\");
+    }
+
+    #[test]
+    fn start_nodes_are_dropped_with_their_edges() {
+        let space = DotTranslator::translate(
+            "digraph { start [shape=point]; 0; 1 [style=filled]; start -> 0; 0 -> start; 0 -> 1; }",
+        )
+        .unwrap();
+        let infos = space
+            .states
+            .iter()
+            .map(|s| s.info.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(infos, ["0", "1"]);
+        let edges = space
+            .transitions
+            .iter()
+            .map(|t| (t.from, t.to))
+            .collect::<Vec<_>>();
+        assert_eq!(edges, [(1, 2)]);
+        assert_eq!(space.initial, [1].into());
+    }
+
+    #[test]
+    fn start_markers() {
+        let space = DotTranslator::translate(
+            r#"digraph {
+                s [shape=none, label=""]; s -> 0;
+                note [shape=plaintext, label=""]; note -> 0; note -> 1;
+                idle [shape=none]; idle -> 1;
+                0 -> 1;
+            }"#,
+        )
+        .unwrap();
+        let infos = space
+            .states
+            .iter()
+            .map(|s| s.info.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(infos, ["0", "", "1", "idle"]);
+        assert_eq!(space.initial, [1].into());
+    }
+
+    #[test]
+    fn attribute_defaults() {
+        let space = DotTranslator::translate(
+            r#"digraph {
+                node [shape=point];
+                init;
+                node [shape=circle];
+                init -> 0 -> 1;
+                subgraph { node [shape=doublecircle]; edge [label=e]; 2; 1 -> 2; }
+                3; 2 -> 3;
+            }"#,
+        )
+        .unwrap();
+        let info = |index: &usize| {
+            space
+                .states
+                .iter()
+                .find(|s| s.index == *index)
+                .unwrap()
+                .info
+                .as_str()
+        };
+        assert_eq!(space.initial.iter().map(info).collect::<Vec<_>>(), ["0"]);
+        assert_eq!(space.accepting.iter().map(info).collect::<Vec<_>>(), ["2"]);
+        let labels = space
+            .transitions
+            .iter()
+            .map(|t| t.label.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, [None, Some("e"), None]);
+    }
+
+    #[test]
+    fn exported_graph_reads_back() {
+        let states = vec![
+            State::new(0, r#"path = "C:\dir\""#.to_string()),
+            State::new(1, "a\\nb".to_string()),
+            State::new(2, "two\nlines".to_string()),
+        ];
+        let transitions = vec![
+            Transition::new(0, 1)
+                .with_label("say \"hi\"\\".to_string())
+                .with_weight(0.5),
+            Transition::new(1, 2).with_weight(0.25),
+            Transition::new(2, 0),
+        ];
+        let space = StateSpace::new(states, transitions)
+            .with_initial([0])
+            .with_accepting([2]);
+        let graph = Graph::<State, Transition, Directed>::try_from(&space).unwrap();
+
+        let dot = to_dot(&graph, &SettingsStyle::default(), true);
+        let read = DotTranslator::translate(&dot).unwrap();
+
+        let info = |index: usize| {
+            let state = read.states.iter().find(|s| s.index == index).unwrap();
+            state.info.as_str()
+        };
+        let infos = read
+            .states
+            .iter()
+            .map(|s| info(s.index))
+            .collect::<Vec<_>>();
+        assert_eq!(infos, [r#"path = "C:\dir\""#, "a\\nb", "two\nlines"]);
+        let transitions = read
+            .transitions
+            .iter()
+            .map(|t| (info(t.from), info(t.to), t.label.as_deref(), t.weight))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            transitions,
+            [
+                (infos[0], infos[1], Some("say \"hi\"\\"), Some(0.5)),
+                (infos[1], infos[2], None, Some(0.25)),
+                (infos[2], infos[0], None, None)
+            ]
+        );
+        let initial = read.initial.iter().map(|i| info(*i)).collect::<Vec<_>>();
+        assert_eq!(initial, [infos[0]]);
+        let accepting = read.accepting.iter().map(|i| info(*i)).collect::<Vec<_>>();
+        assert_eq!(accepting, [infos[2]]);
+    }
+}
@@ -0,0 +1,386 @@
+use std::{collections::HashMap, io::ErrorKind, path::Path};
+
+use crate::graph::{state::State, transition::Transition, StateSpace};
+
+use super::{column_of, parse_id, ParseError, Translator};
+
+/// Reads models exported by PRISM in its explicit format.
+///
+/// The transition matrix (`.tra`) is required. For DTMCs and CTMCs each line
+/// is `<from> <to> <probability or rate> [action]`, for MDPs it is
+/// `<from> <choice> <to> <probability> [action]`. The optional state file
+/// (`.sta`) provides the variable valuations used as state info, and the
+/// optional label file (`.lab`) provides labels such as `init` and `deadlock`,
+/// which become the atomic propositions of the states. States labelled `init`
+/// are the initial states.
+pub struct PrismTranslator;
+
+impl Translator for PrismTranslator {
+    type ErrorType = ParseError;
+    fn translate(str: &str) -> Result<StateSpace, ParseError> {
+        Self::translate_model(str, None, None)
+    }
+}
+
+impl PrismTranslator {
+    /// Combines the `.tra` file at `path` with the `.sta` and `.lab` files next
+    /// to it, if they exist. A sibling that exists but cannot be read fails the
+    /// translation.
+    pub fn translate_files(tra: &str, path: Option<&Path>) -> Result<StateSpace, ParseError> {
+        let read_sibling = |file: &'static str| {
+            let Some(path) = path else {
+                return Ok(None);
+            };
+            match std::fs::read_to_string(path.with_extension(file.trim_start_matches('.'))) {
+                Ok(content) => Ok(Some(content)),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(ParseError::Io {
+                    line: 1,
+                    message: e.to_string(),
+                }
+                .in_file(file)),
+            }
+        };
+        let (sta, lab) = (read_sibling(".sta")?, read_sibling(".lab")?);
+        Self::translate_model(tra, sta.as_deref(), lab.as_deref())
+    }
+
+    /// Combines the contents of the `.tra`, `.sta` and `.lab` files of a model.
+    pub fn translate_model(
+        tra: &str,
+        sta: Option<&str>,
+        lab: Option<&str>,
+    ) -> Result<StateSpace, ParseError> {
+        let (nstates, edges) = parse_tra(tra).map_err(|e| e.in_file(".tra"))?;
+        if nstates == 0 {
+            return Err(ParseError::Empty);
+        }
+
+        let mut nodes = (0..nstates)
+            .map(|id| State::new(id, id.to_string()))
+            .collect::<Vec<_>>();
+        if let Some(sta) = sta {
+            for (id, info) in parse_sta(sta, nstates).map_err(|e| e.in_file(".sta"))? {
+                nodes[id].info = info;
+            }
+        }
+        if let Some(lab) = lab {
+            for (id, props) in parse_lab(lab, nstates).map_err(|e| e.in_file(".lab"))? {
+                nodes[id].props = props;
+            }
+        }
+        let initial = nodes
+            .iter()
+            .filter(|state| state.props.iter().any(|prop| prop == "init"))
+            .map(|state| state.index)
+            .collect::<Vec<_>>();
+
+        Ok(StateSpace::new(nodes, edges).with_initial(initial))
+    }
+}
+
+/// Non-empty lines together with their line number.
+fn numbered_lines(str: &str) -> impl Iterator<Item = (usize, &str)> {
+    str.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+}
+
+fn check_state(
+    line_no: usize,
+    line: &str,
+    token: &str,
+    nstates: usize,
+) -> Result<usize, ParseError> {
+    let id = parse_id(line_no, line, token, "state index")?;
+    if id >= nstates {
+        let token = token.trim();
+        return Err(ParseError::unexpected(
+            line_no,
+            column_of(line, token),
+            token,
+            "state index below the number of states",
+        ));
+    }
+    Ok(id)
+}
+
+fn parse_tra(tra: &str) -> Result<(usize, Vec<Transition>), ParseError> {
+    let mut lines = numbered_lines(tra);
+    let Some((header_no, header)) = lines.next() else {
+        return Err(ParseError::Empty);
+    };
+    // `<states> <transitions>` for DTMCs and CTMCs,
+    // `<states> <choices> <transitions>` for MDPs
+    let fields = header.split_whitespace().collect::<Vec<_>>();
+    if fields.len() != 2 && fields.len() != 3 {
+        let trimmed = header.trim();
+        return Err(ParseError::unexpected(
+            header_no,
+            column_of(header, trimmed),
+            trimmed,
+            "`<states> <transitions>` or `<states> <choices> <transitions>`",
+        ));
+    }
+    let mdp = fields.len() == 3;
+    let nstates = parse_id(header_no, header, fields[0], "number of states")?;
+    let ntrans_token = fields[fields.len() - 1];
+    let ntrans = parse_id(header_no, header, ntrans_token, "number of transitions")?;
+
+    let mut edges = vec![];
+    for (line_no, line) in lines {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        let (from, choice, to, weight, action) = match (mdp, fields.as_slice()) {
+            (false, [from, to, weight, rest @ ..]) => (from, None, to, weight, rest),
+            (true, [from, choice, to, weight, rest @ ..]) => (from, Some(choice), to, weight, rest),
+            _ => {
+                let trimmed = line.trim();
+                return Err(ParseError::unexpected(
+                    line_no,
+                    column_of(line, trimmed),
+                    trimmed,
+                    match mdp {
+                        true => "`<from> <choice> <to> <probability> [action]`",
+                        false => "`<from> <to> <probability> [action]`",
+                    },
+                ));
+            }
+        };
+        let from = check_state(line_no, line, from, nstates)?;
+        let to = check_state(line_no, line, to, nstates)?;
+        let weight = weight.parse::<f64>().map_err(|_| {
+            ParseError::unexpected(
+                line_no,
+                column_of(line, weight),
+                weight,
+                "probability or rate",
+            )
+        })?;
+
+        let mut transition = Transition::new(from, to).with_weight(weight);
+        // fall back to the choice index to tell nondeterministic choices apart
+        match (action.first(), choice) {
+            (Some(action), _) => transition = transition.with_label(action.to_string()),
+            (None, Some(choice)) => transition = transition.with_label(choice.to_string()),
+            (None, None) => {}
+        }
+        edges.push(transition);
+    }
+
+    if edges.len() != ntrans {
+        return Err(ParseError::Syntax {
+            line: header_no,
+            column: column_of(header, ntrans_token),
+            message: format!(
+                "header declares {} transitions, found {}",
+                ntrans,
+                edges.len()
+            ),
+        });
+    }
+    Ok((nstates, edges))
+}
+
+/// Parses `(x,y)` followed by lines `<state>:(<x>,<y>)` into `x=.., y=..` infos.
+fn parse_sta(sta: &str, nstates: usize) -> Result<Vec<(usize, String)>, ParseError> {
+    let mut lines = numbered_lines(sta);
+    let Some((header_no, header)) = lines.next() else {
+        return Ok(vec![]);
+    };
+    let trimmed = header.trim();
+    let vars = trimmed
+        .strip_prefix('(')
+        .and_then(|vars| vars.strip_suffix(')'))
+        .ok_or_else(|| {
+            ParseError::unexpected(
+                header_no,
+                column_of(header, trimmed),
+                trimmed,
+                "`(<variable>,...)`",
+            )
+        })?
+        .split(',')
+        .map(str::trim)
+        .collect::<Vec<_>>();
+
+    let mut infos = vec![];
+    for (line_no, line) in lines {
+        let trimmed = line.trim();
+        let malformed = || {
+            ParseError::unexpected(
+                line_no,
+                column_of(line, trimmed),
+                trimmed,
+                "`<state>:(<value>,...)`",
+            )
+        };
+        let (id, values) = trimmed.split_once(':').ok_or_else(malformed)?;
+        let id = check_state(line_no, line, id, nstates)?;
+        let values = values
+            .trim()
+            .strip_prefix('(')
+            .and_then(|values| values.strip_suffix(')'))
+            .ok_or_else(malformed)?
+            .split(',')
+            .collect::<Vec<_>>();
+        if values.len() != vars.len() {
+            return Err(malformed());
+        }
+        let info = vars
+            .iter()
+            .zip(values)
+            .map(|(var, value)| format!("{}={}", var, value.trim()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        infos.push((id, info));
+    }
+
+    Ok(infos)
+}
+
+/// Parses `0="init" 1="deadlock"` followed by lines `<state>: <label>...`.
+fn parse_lab(lab: &str, nstates: usize) -> Result<Vec<(usize, Vec<String>)>, ParseError> {
+    let mut lines = numbered_lines(lab);
+    let Some((header_no, header)) = lines.next() else {
+        return Ok(vec![]);
+    };
+    let mut names = HashMap::new();
+    for declaration in header.split_whitespace() {
+        let (index, name) = declaration.split_once('=').ok_or_else(|| {
+            ParseError::unexpected(
+                header_no,
+                column_of(header, declaration),
+                declaration,
+                "`<index>=\"<label>\"`",
+            )
+        })?;
+        let index = parse_id(header_no, header, index, "label index")?;
+        names.insert(index, name.trim_matches('"').to_string());
+    }
+
+    let mut props = vec![];
+    for (line_no, line) in lines {
+        let trimmed = line.trim();
+        let (id, labels) = trimmed.split_once(':').ok_or_else(|| {
+            ParseError::unexpected(
+                line_no,
+                column_of(line, trimmed),
+                trimmed,
+                "`<state>: <label>...`",
+            )
+        })?;
+        let id = check_state(line_no, line, id, nstates)?;
+        let labels = labels
+            .split_whitespace()
+            .map(|label| {
+                let index = parse_id(line_no, line, label, "label index")?;
+                names.get(&index).cloned().ok_or_else(|| {
+                    ParseError::unexpected(
+                        line_no,
+                        column_of(line, label),
+                        label,
+                        "declared label index",
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        props.push((id, labels));
+    }
+
+    Ok(props)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    const TRA: &str = "3 3\n0 1 0.5 go\n0 2 0.5\n1 1 1\n";
+    const STA: &str = "(x,y)\n0:(0,false)\n1:(1,false)\n2:(1,true)\n";
+    const LAB: &str = "0=\"init\" 1=\"deadlock\"\n0: 0\n2: 1\n";
+
+    /// A fresh directory holding `model.tra` and the given siblings.
+    fn model_dir(name: &str, siblings: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("graphew-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir(&dir).unwrap();
+        for (file, content) in siblings {
+            std::fs::write(dir.join(file), content).unwrap();
+        }
+        dir.join("model.tra")
+    }
+
+    #[test]
+    fn dtmc_with_states_and_labels() {
+        let space = PrismTranslator::translate_model(TRA, Some(STA), Some(LAB)).unwrap();
+        assert_eq!(space.states[2].info, "x=1, y=true");
+        assert_eq!(space.states[2].props, ["deadlock"]);
+        assert_eq!(space.initial, [0].into());
+        let transitions = space
+            .transitions
+            .iter()
+            .map(|t| (t.from, t.to, t.label.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            transitions,
+            [(0, 1, Some("go")), (0, 2, None), (1, 1, None)]
+        );
+        assert_eq!(space.transitions[0].weight, Some(0.5));
+    }
+
+    #[test]
+    fn mdp_choices_label_transitions() {
+        let space = PrismTranslator::translate("2 2 2\n0 0 1 1\n0 1 0 1 stay\n").unwrap();
+        let labels = space
+            .transitions
+            .iter()
+            .map(|t| t.label.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, [Some("0"), Some("stay")]);
+    }
+
+    #[test]
+    fn transition_count_is_checked() {
+        assert_eq!(
+            PrismTranslator::translate("2 3\n0 1 1\n1 0 1\n").unwrap_err(),
+            ParseError::Syntax {
+                line: 1,
+                column: 3,
+                message: "header declares 3 transitions, found 2".to_string(),
+            }
+            .in_file(".tra")
+        );
+    }
+
+    #[test]
+    fn state_out_of_range() {
+        assert_eq!(
+            PrismTranslator::translate("2 1\n0 2 1\n").unwrap_err(),
+            ParseError::unexpected(2, 3, "2", "state index below the number of states")
+                .in_file(".tra")
+        );
+    }
+
+    #[test]
+    fn missing_siblings_are_ignored() {
+        let path = model_dir("prism-missing", &[("model.sta", STA)]);
+        let space = PrismTranslator::translate_files(TRA, Some(&path)).unwrap();
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert_eq!(space.states[1].info, "x=1, y=false");
+        assert!(space.initial.is_empty());
+    }
+
+    #[test]
+    fn unreadable_sibling_fails() {
+        let path = model_dir("prism-unreadable", &[]);
+        // a directory exists but cannot be read as a file
+        std::fs::create_dir(path.with_extension("lab")).unwrap();
+        let error = PrismTranslator::translate_files(TRA, Some(&path)).unwrap_err();
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert!(
+            matches!(&error, ParseError::InFile { file: ".lab", error } if matches!(**error, ParseError::Io { .. }))
+        );
+    }
+}
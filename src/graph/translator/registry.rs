@@ -0,0 +1,245 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+use crate::graph::StateSpace;
+
+use super::{
+    AutTranslator, DotTranslator, KripkeJsonTranslator, ParseError, PrismTranslator,
+    SLIMTranslator, SPINTranslator, StreamTranslator, Translator,
+};
+
+/// Number of bytes at the start of a file that probes look at.
+pub const PROBE_LEN: usize = 4096;
+
+/// How a format turns its input into a [`StateSpace`].
+#[derive(Clone, Copy)]
+pub enum Reader {
+    Text(fn(&str) -> Result<StateSpace, ParseError>),
+    /// Translates while reading, see [`StreamTranslator`].
+    Stream(fn(&mut dyn BufRead) -> Result<StateSpace, ParseError>),
+    /// Also reads files next to the input at the given path, if any.
+    WithSiblings(fn(&str, Option<&Path>) -> Result<StateSpace, ParseError>),
+}
+
+/// An input format known to Graphew.
+pub struct Format {
+    pub name: &'static str,
+    /// File extensions without the leading dot.
+    pub extensions: &'static [&'static str],
+    /// Whether the start of a file looks like this format.
+    pub probe: fn(&str) -> bool,
+    pub reader: Reader,
+}
+
+impl Format {
+    pub fn has_extension(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                self.extensions
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(ext))
+            })
+    }
+}
+
+pub static FORMATS: &[Format] = &[
+    Format {
+        name: "slim dump",
+        extensions: &[],
+        probe: probe_slim,
+        reader: Reader::Stream(stream::<SLIMTranslator>),
+    },
+    Format {
+        name: "SPIN trail",
+        extensions: &[],
+        probe: probe_spin,
+        reader: Reader::Stream(stream::<SPINTranslator>),
+    },
+    Format {
+        name: "Kripke JSON",
+        extensions: &["json"],
+        probe: probe_kripke_json,
+        reader: Reader::Text(KripkeJsonTranslator::translate),
+    },
+    Format {
+        name: "Aldebaran LTS",
+        extensions: &["aut"],
+        probe: probe_aut,
+        reader: Reader::Stream(stream::<AutTranslator>),
+    },
+    Format {
+        name: "Graphviz DOT",
+        extensions: &["dot", "gv"],
+        probe: probe_dot,
+        reader: Reader::Text(DotTranslator::translate),
+    },
+    Format {
+        name: "PRISM model",
+        extensions: &["tra"],
+        probe: probe_prism,
+        reader: Reader::WithSiblings(PrismTranslator::translate_files),
+    },
+];
+
+/// The format called `name`.
+pub fn find(name: &str) -> Option<&'static Format> {
+    FORMATS.iter().find(|format| format.name == name)
+}
+
+fn stream<T: StreamTranslator>(reader: &mut dyn BufRead) -> Result<StateSpace, ParseError> {
+    T::translate_reader(reader)
+}
+
+/// Formats the file at `path` starting with `head` may be in.
+///
+/// Formats whose probe accepts `head` are preferred; among several of them
+/// those with a matching extension win. Without any accepting probe the
+/// formats with a matching extension are returned. More than one result
+/// means the format is ambiguous.
+pub fn detect(path: Option<&Path>, head: &str) -> Vec<&'static Format> {
+    let has_extension = |format: &&Format| path.is_some_and(|path| format.has_extension(path));
+    let probed = FORMATS
+        .iter()
+        .filter(|format| (format.probe)(head))
+        .collect::<Vec<_>>();
+
+    match probed.len() {
+        0 => FORMATS.iter().filter(has_extension).collect(),
+        1 => probed,
+        _ => {
+            let by_extension = probed
+                .iter()
+                .copied()
+                .filter(has_extension)
+                .collect::<Vec<_>>();
+            match by_extension.is_empty() {
+                true => probed,
+                false => by_extension,
+            }
+        }
+    }
+}
+
+/// Detects the format of `reader` from its first [`PROBE_LEN`] bytes and
+/// translates it.
+pub fn translate_detected(reader: &mut dyn BufRead) -> Result<StateSpace, ParseError> {
+    let io_error = |e: std::io::Error| ParseError::Io {
+        line: 1,
+        message: e.to_string(),
+    };
+    // pipes may hand out less than the head at a time
+    let mut head = Vec::with_capacity(PROBE_LEN);
+    reader
+        .take(PROBE_LEN as u64)
+        .read_to_end(&mut head)
+        .map_err(io_error)?;
+    let [format] = detect(None, &String::from_utf8_lossy(&head))[..] else {
+        return Err(ParseError::UnknownFormat);
+    };
+    let reader = &mut BufReader::new(head.as_slice().chain(reader));
+
+    match format.reader {
+        Reader::Stream(translate) => translate(reader),
+        Reader::Text(translate) => {
+            let mut content = String::new();
+            reader.read_to_string(&mut content).map_err(io_error)?;
+            translate(&content)
+        }
+        Reader::WithSiblings(translate) => {
+            let mut content = String::new();
+            reader.read_to_string(&mut content).map_err(io_error)?;
+            translate(&content, None)
+        }
+    }
+}
+
+/// The first line that is not blank.
+fn first_line(head: &str) -> &str {
+    head.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("")
+}
+
+fn probe_slim(head: &str) -> bool {
+    // the same rule as the translator
+    head.lines().any(|line| line.trim().starts_with("States"))
+}
+
+fn probe_spin(head: &str) -> bool {
+    let line = first_line(head);
+    line == "using statement merging"
+        || line.split_once(':').is_some_and(|(step, rest)| {
+            step.parse::<usize>().is_ok() && rest.trim_start().starts_with("proc ")
+        })
+}
+
+fn probe_kripke_json(head: &str) -> bool {
+    head.trim_start().starts_with('{') && head.contains("\"states\"")
+}
+
+fn probe_aut(head: &str) -> bool {
+    first_line(head)
+        .strip_prefix("des")
+        .is_some_and(|rest| rest.trim_start().starts_with('('))
+}
+
+fn probe_dot(head: &str) -> bool {
+    let keyword = head
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with("//"))
+        .flat_map(str::split_whitespace)
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    matches!(keyword.as_str(), "strict" | "graph" | "digraph")
+        || keyword.starts_with("digraph{")
+        || keyword.starts_with("graph{")
+}
+
+fn probe_prism(head: &str) -> bool {
+    let fields = first_line(head).split_whitespace().collect::<Vec<_>>();
+    (fields.len() == 2 || fields.len() == 3)
+        && fields.iter().all(|field| field.parse::<usize>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    /// Hands out one byte per read, like a slow pipe.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let Some((first, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+            buf[0] = *first;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn detects_input_arriving_in_pieces() {
+        let input = "des (0, 2, 2)\n(0, \"a\", 1)\n(1, \"b\", 0)\n";
+        let mut reader = BufReader::new(Trickle(input.as_bytes()));
+        let space = translate_detected(&mut reader).unwrap();
+        assert_eq!(space.states.len(), 2);
+        assert_eq!(space.transitions.len(), 2);
+    }
+
+    #[test]
+    fn slim_header_is_probed_like_it_is_parsed() {
+        assert!(probe_slim("States (3)\n0::a.\n"));
+        assert!(probe_slim("  States\n"));
+        assert!(!probe_slim("0::a.\n"));
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+};
+
+use super::{state::State, StateSpace};
+
+/// Number of items listed per problem in the report message.
+const LISTED_ITEMS: usize = 10;
+
+/// Problems found in a [`StateSpace`] by [`StateSpace::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Transitions whose source or target state is not declared.
+    pub dangling: Vec<(usize, usize)>,
+    /// State indices that are declared more than once.
+    pub duplicates: Vec<usize>,
+    /// States that cannot be reached from any initial state.
+    pub unreachable: Vec<usize>,
+}
+
+impl ValidationReport {
+    pub fn is_empty(&self) -> bool {
+        self.dangling.is_empty() && self.duplicates.is_empty() && self.unreachable.is_empty()
+    }
+
+    /// Whether the state space cannot be turned into a graph as it is.
+    pub fn is_fatal(&self) -> bool {
+        !self.dangling.is_empty() || !self.duplicates.is_empty()
+    }
+}
+
+fn list<T>(items: &[T], f: impl Fn(&T) -> String) -> String {
+    let mut listed = items
+        .iter()
+        .take(LISTED_ITEMS)
+        .map(f)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if items.len() > LISTED_ITEMS {
+        listed.push_str(", ...");
+    }
+    listed
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec![];
+        if !self.dangling.is_empty() {
+            lines.push(format!(
+                "{} transitions point at undeclared states: {}",
+                self.dangling.len(),
+                list(&self.dangling, |(from, to)| format!("{} -> {}", from, to))
+            ));
+        }
+        if !self.duplicates.is_empty() {
+            lines.push(format!(
+                "{} state indices are declared more than once: {}",
+                self.duplicates.len(),
+                list(&self.duplicates, usize::to_string)
+            ));
+        }
+        if !self.unreachable.is_empty() {
+            lines.push(format!(
+                "{} states are unreachable from the initial states: {}",
+                self.unreachable.len(),
+                list(&self.unreachable, usize::to_string)
+            ));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+impl StateSpace {
+    /// Checks for dangling transitions, duplicate state indices and, if
+    /// initial states are known, unreachable states.
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        let mut declared = HashSet::new();
+        for state in &self.states {
+            if !declared.insert(state.index) && !report.duplicates.contains(&state.index) {
+                report.duplicates.push(state.index);
+            }
+        }
+
+        let mut successors: HashMap<usize, Vec<usize>> = HashMap::new();
+        for transition in &self.transitions {
+            if !declared.contains(&transition.from) || !declared.contains(&transition.to) {
+                report.dangling.push((transition.from, transition.to));
+                continue;
+            }
+            successors
+                .entry(transition.from)
+                .or_default()
+                .push(transition.to);
+        }
+
+        if self.initial.is_empty() {
+            return report;
+        }
+        let mut visited = HashSet::new();
+        let mut queue = self
+            .initial
+            .iter()
+            .filter(|index| declared.contains(index))
+            .copied()
+            .collect::<VecDeque<_>>();
+        while let Some(index) = queue.pop_front() {
+            if !visited.insert(index) {
+                continue;
+            }
+            if let Some(targets) = successors.get(&index) {
+                queue.extend(targets.iter().filter(|to| !visited.contains(to)));
+            }
+        }
+        let mut listed = HashSet::new();
+        report.unreachable = self
+            .states
+            .iter()
+            .map(|state| state.index)
+            .filter(|index| !visited.contains(index) && listed.insert(*index))
+            .collect();
+
+        report
+    }
+
+    /// Makes the state space convertible into a graph by keeping only the
+    /// first declaration of duplicate states and creating placeholder states
+    /// for undeclared transition endpoints.
+    pub fn repair(mut self) -> Self {
+        let mut declared = HashSet::new();
+        self.states.retain(|state| declared.insert(state.index));
+
+        for transition in &self.transitions {
+            for index in [transition.from, transition.to] {
+                if declared.insert(index) {
+                    self.states
+                        .push(State::new(index, format!("<missing state {}>", index)));
+                }
+            }
+        }
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::transition::Transition;
+
+    fn state_space(states: &[usize], transitions: &[(usize, usize)]) -> StateSpace {
+        StateSpace::new(
+            states
+                .iter()
+                .map(|index| State::new(*index, index.to_string()))
+                .collect(),
+            transitions
+                .iter()
+                .map(|(from, to)| Transition::new(*from, *to))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn valid_state_space() {
+        let report = state_space(&[0, 1], &[(0, 1), (1, 0)])
+            .with_initial([0])
+            .validate();
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn duplicates_are_listed_once() {
+        let report = state_space(&[0, 1, 1, 1], &[(0, 1)]).validate();
+        assert_eq!(report.duplicates, [1]);
+        assert!(report.is_fatal());
+    }
+
+    #[test]
+    fn dangling_transitions() {
+        let report = state_space(&[0], &[(0, 1), (2, 0), (0, 0)]).validate();
+        assert_eq!(report.dangling, [(0, 1), (2, 0)]);
+        assert!(report.is_fatal());
+    }
+
+    #[test]
+    fn unreachable_states() {
+        let space = state_space(&[0, 1, 2, 3], &[(0, 1), (2, 3), (3, 2)]);
+        // without initial states nothing counts as unreachable
+        assert!(space.validate().is_empty());
+
+        let report = space.with_initial([0]).validate();
+        assert_eq!(report.unreachable, [2, 3]);
+        assert!(!report.is_fatal());
+    }
+
+    #[test]
+    fn repair_makes_the_state_space_valid() {
+        let mut space = state_space(&[0, 1, 1], &[(0, 1), (1, 2)]);
+        space.states[2].info = "second".to_string();
+        let repaired = space.repair();
+
+        let states = repaired
+            .states
+            .iter()
+            .map(|state| (state.index, state.info.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(states, [(0, "0"), (1, "1"), (2, "<missing state 2>")]);
+        assert!(repaired.validate().is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 use egui_graphs::Graph;
 use petgraph::Directed;
 
+use crate::graph::{state::State, transition::Transition};
+
 use self::radial::RadialLayout;
 
 pub mod radial;
@@ -11,9 +13,14 @@ pub enum Layout {
 }
 
 impl Layout {
-    pub fn layout<N: Clone, E: Clone>(&self, graph: &mut Graph<N, E, Directed>) {
+    /// Moves the states of `graph`, starting from its initial states.
+    pub fn layout(&self, graph: &mut Graph<State, Transition, Directed>) {
+        let roots = graph
+            .node_indices()
+            .filter(|idx| graph[*idx].data().is_some_and(|state| state.initial))
+            .collect::<Vec<_>>();
         match self {
-            Layout::Radial => RadialLayout::layout(graph),
+            Layout::Radial => RadialLayout::layout(graph, &roots),
         }
     }
 }
@@ -3,22 +3,41 @@ use std::{
     f32::consts::PI,
 };
 
+use petgraph::stable_graph::NodeIndex;
+
 #[derive(Debug, Default)]
 pub struct RadialLayout;
 
 impl RadialLayout {
-    pub fn layout<N: Clone, E: Clone>(graph: &mut egui_graphs::Graph<N, E, petgraph::Directed>) {
+    /// Lays the graph out in rings around `roots`, usually the initial
+    /// states. A single root sits in the center, several roots form the first
+    /// ring. Without roots the first node is used.
+    pub fn layout<N: Clone, E: Clone>(
+        graph: &mut egui_graphs::Graph<N, E, petgraph::Directed>,
+        roots: &[NodeIndex],
+    ) {
         let mut queue = VecDeque::new();
         let mut visited = HashSet::new();
 
-        let g_indices = graph.node_indices().collect::<Vec<_>>();
-        let root = g_indices[0];
-        visited.insert(root.index());
-        let g_node = graph.node_weight_mut(root).unwrap();
-        g_node.set_location(egui::Vec2 { x: 0.0, y: 0.0 });
-        let childs = graph
-            .neighbors_directed(root, petgraph::Direction::Outgoing)
-            .collect::<Vec<_>>();
+        let first_level = match roots {
+            [] => match graph.node_indices().next() {
+                Some(first) => vec![first],
+                None => return,
+            },
+            roots => roots.to_vec(),
+        };
+        let childs = match first_level[..] {
+            [root] => {
+                visited.insert(root.index());
+                let g_node = graph.node_weight_mut(root).unwrap();
+                g_node.set_location(egui::Vec2 { x: 0.0, y: 0.0 });
+                graph
+                    .neighbors_directed(root, petgraph::Direction::Outgoing)
+                    .filter(|child| *child != root)
+                    .collect::<Vec<_>>()
+            }
+            _ => first_level,
+        };
         let len = childs.len() as f32;
         for (i, child) in childs.iter().enumerate() {
             let node = graph.node_weight_mut(*child).unwrap();
@@ -71,3 +90,49 @@ fn calc_r(v: f32, sibling: bool) -> f32 {
         false => v / 2.0,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use egui::Vec2;
+    use egui_graphs::{Edge, Graph, Node};
+
+    use super::*;
+
+    fn chain(len: usize) -> (Graph<(), (), petgraph::Directed>, Vec<NodeIndex>) {
+        let mut graph = Graph::default();
+        let nodes = (0..len)
+            .map(|_| graph.add_node(Node::new(Vec2::new(1., 1.), ())))
+            .collect::<Vec<_>>();
+        for pair in nodes.windows(2) {
+            graph.add_edge(pair[0], pair[1], Edge::new(()));
+        }
+        (graph, nodes)
+    }
+
+    #[test]
+    fn single_root_is_centered() {
+        let (mut graph, nodes) = chain(3);
+        RadialLayout::layout(&mut graph, &[nodes[1]]);
+        assert_eq!(graph[nodes[1]].location(), Vec2::ZERO);
+        assert!((graph[nodes[2]].location().length() - 160.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn several_roots_form_the_first_ring() {
+        let (mut graph, nodes) = chain(3);
+        RadialLayout::layout(&mut graph, &[nodes[0], nodes[2]]);
+        for root in [nodes[0], nodes[2]] {
+            assert!((graph[root].location().length() - 160.).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn without_roots() {
+        let (mut graph, nodes) = chain(2);
+        RadialLayout::layout(&mut graph, &[]);
+        assert_eq!(graph[nodes[0]].location(), Vec2::ZERO);
+
+        let (mut empty, _) = chain(0);
+        RadialLayout::layout(&mut empty, &[]);
+    }
+}
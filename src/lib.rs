@@ -1,8 +1,14 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+pub mod analysis;
 mod app;
+pub mod export;
 pub mod graph;
 pub mod layout;
+pub mod live;
+pub mod loader;
+pub mod reload;
+pub mod session;
 pub mod settings;
 pub mod views;
 pub use app::MainApp;
@@ -0,0 +1,619 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{self, BufRead, BufReader, ErrorKind, Read},
+    net::TcpListener,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use crossbeam::channel::{unbounded, Receiver, Sender};
+use egui::Vec2;
+use egui_graphs::{Graph, Node};
+use fdg_sim::{glam::Vec3, Simulation};
+use petgraph::{stable_graph::NodeIndex, Directed, Direction};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::graph::{state::State, transition::Transition, translator::Event};
+
+/// Time between two checks whether the stream was stopped while waiting.
+const WAIT_INTERVAL: Duration = Duration::from_millis(100);
+/// Size of the area states without a neighbour are placed in.
+const SPAWN_SIZE: f32 = 250.;
+/// Largest distance of a new state from the state it is connected to.
+const PLACEMENT_SPREAD: f32 = 20.;
+
+/// Where a model checker sends its events to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LiveSource {
+    /// A TCP address to listen on, e.g. `127.0.0.1:7878`.
+    Tcp(String),
+    /// A Unix socket to create.
+    #[cfg(unix)]
+    Unix(std::path::PathBuf),
+    /// The standard input. It cannot be stopped while it waits for a line,
+    /// so stopping only takes effect once the next line or the end arrives.
+    Stdin,
+}
+
+impl FromStr for LiveSource {
+    type Err = String;
+
+    /// Parses `tcp:<address>`, `unix:<path>` or `-` for the standard input.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "-" {
+            return Ok(LiveSource::Stdin);
+        }
+        match s.split_once(':') {
+            Some(("tcp", address)) => Ok(LiveSource::Tcp(address.to_string())),
+            #[cfg(unix)]
+            Some(("unix", path)) => Ok(LiveSource::Unix(path.into())),
+            _ => Err(format!(
+                "`{}` is not a source, expected `tcp:<address>`, `unix:<path>` or `-`",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for LiveSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiveSource::Tcp(address) => write!(f, "tcp:{}", address),
+            #[cfg(unix)]
+            LiveSource::Unix(path) => write!(f, "unix:{}", path.display()),
+            LiveSource::Stdin => write!(f, "standard input"),
+        }
+    }
+}
+
+/// A line of the live protocol.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Message {
+    State {
+        id: usize,
+        #[serde(default)]
+        info: String,
+        #[serde(default)]
+        props: Vec<String>,
+        #[serde(default)]
+        initial: bool,
+        #[serde(default)]
+        accepting: bool,
+    },
+    Transition {
+        from: usize,
+        to: usize,
+        label: Option<String>,
+        weight: Option<f64>,
+    },
+    /// The exploration is complete.
+    Done,
+}
+
+pub enum LiveMessage {
+    /// A model checker connected and starts a new exploration.
+    Connected(String),
+    Event(Event),
+    /// The exploration is complete.
+    Done,
+    Disconnected,
+    /// A line could not be read. The stream goes on with the next line.
+    Invalid(String),
+    /// The stream ended because of an error.
+    Failed(String),
+}
+
+/// Receives the events of a model checker on a background thread.
+pub struct LiveStream {
+    pub source: LiveSource,
+    /// Who is connected at the moment, if anyone.
+    pub peer: Option<String>,
+    /// Number of lines of the current connection that could not be read.
+    pub invalid: usize,
+    /// Why the last of the `invalid` lines could not be read.
+    pub last_invalid: Option<String>,
+    receiver: Receiver<LiveMessage>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl LiveStream {
+    /// Starts listening on `source`. Fails if it cannot be bound.
+    pub fn start(source: LiveSource, ctx: egui::Context) -> Result<Self, String> {
+        let (sender, receiver) = unbounded();
+        let stopped = Arc::new(AtomicBool::new(false));
+        let worker = Worker {
+            sender,
+            stopped: stopped.clone(),
+            ctx,
+        };
+        let describe = |e: io::Error| format!("{}: {}", source, e);
+
+        match &source {
+            LiveSource::Tcp(address) => {
+                let listener = TcpListener::bind(address).map_err(describe)?;
+                listener.set_nonblocking(true).map_err(describe)?;
+                thread::spawn(move || {
+                    worker.accept(|| {
+                        let (stream, peer) = listener.accept()?;
+                        stream.set_nonblocking(false)?;
+                        stream.set_read_timeout(Some(WAIT_INTERVAL))?;
+                        Ok((stream, peer.to_string()))
+                    })
+                });
+            }
+            #[cfg(unix)]
+            LiveSource::Unix(path) => {
+                let listener = bind_unix(path).map_err(describe)?;
+                listener.set_nonblocking(true).map_err(describe)?;
+                let path = path.clone();
+                thread::spawn(move || {
+                    worker.accept(|| {
+                        let (stream, _) = listener.accept()?;
+                        stream.set_nonblocking(false)?;
+                        stream.set_read_timeout(Some(WAIT_INTERVAL))?;
+                        Ok((stream, path.display().to_string()))
+                    });
+                    let _ = std::fs::remove_file(&path);
+                });
+            }
+            LiveSource::Stdin => {
+                thread::spawn(move || {
+                    worker.send(LiveMessage::Connected("standard input".to_string()));
+                    worker.serve(io::stdin().lock());
+                });
+            }
+        }
+
+        Ok(Self {
+            source,
+            peer: None,
+            invalid: 0,
+            last_invalid: None,
+            receiver,
+            stopped,
+        })
+    }
+
+    pub fn poll(&mut self) -> Option<LiveMessage> {
+        let message = self.receiver.try_recv().ok()?;
+        match &message {
+            LiveMessage::Connected(peer) => {
+                self.peer = Some(peer.clone());
+                self.invalid = 0;
+                self.last_invalid = None;
+            }
+            LiveMessage::Disconnected | LiveMessage::Failed(_) => self.peer = None,
+            LiveMessage::Invalid(error) => {
+                self.invalid += 1;
+                self.last_invalid = Some(error.clone());
+            }
+            _ => {}
+        }
+        Some(message)
+    }
+}
+
+/// Binds a Unix socket at `path`, replacing the socket file a previous run
+/// left behind. A socket somebody still listens on is not replaced.
+#[cfg(unix)]
+fn bind_unix(path: &std::path::Path) -> io::Result<std::os::unix::net::UnixListener> {
+    use std::os::unix::{
+        fs::FileTypeExt,
+        net::{UnixListener, UnixStream},
+    };
+
+    match UnixListener::bind(path) {
+        Err(e) if e.kind() == ErrorKind::AddrInUse => {
+            if !std::fs::symlink_metadata(path)?.file_type().is_socket() {
+                return Err(io::Error::new(
+                    ErrorKind::AddrInUse,
+                    "the path exists and is not a socket",
+                ));
+            }
+            if UnixStream::connect(path).is_ok() {
+                return Err(io::Error::new(
+                    ErrorKind::AddrInUse,
+                    "another program is listening on this socket",
+                ));
+            }
+            std::fs::remove_file(path)?;
+            UnixListener::bind(path)
+        }
+        result => result,
+    }
+}
+
+impl Drop for LiveStream {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+struct Worker {
+    sender: Sender<LiveMessage>,
+    stopped: Arc<AtomicBool>,
+    ctx: egui::Context,
+}
+
+impl Worker {
+    fn send(&self, message: LiveMessage) {
+        let _ = self.sender.send(message);
+        self.ctx.request_repaint();
+    }
+
+    fn stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    /// Serves the connections returned by `accept` one after another.
+    fn accept<R: Read>(&self, mut accept: impl FnMut() -> io::Result<(R, String)>) {
+        while !self.stopped() {
+            match accept() {
+                Ok((stream, peer)) => {
+                    self.send(LiveMessage::Connected(peer));
+                    self.serve(BufReader::new(stream));
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(WAIT_INTERVAL),
+                Err(e) => {
+                    self.send(LiveMessage::Failed(e.to_string()));
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Forwards the events read from `reader` until it ends or the stream
+    /// is stopped.
+    fn serve(&self, mut reader: impl BufRead) {
+        let mut line = vec![];
+        let mut number = 0;
+        while !self.stopped() {
+            match reader.read_until(b'\n', &mut line) {
+                // a read timed out, the bytes read so far stay in `line`
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    continue
+                }
+                Err(e) => {
+                    self.send(LiveMessage::Failed(e.to_string()));
+                    return;
+                }
+                Ok(0) if line.is_empty() => break,
+                // the last line has no line break, it is read before stopping
+                Ok(0) => {}
+                Ok(_) if !line.ends_with(b"\n") => continue,
+                Ok(_) => {}
+            }
+            number += 1;
+            let text = String::from_utf8_lossy(&line);
+            if !text.trim().is_empty() {
+                match serde_json::from_str(&text) {
+                    Ok(message) => self.forward(message),
+                    Err(e) => self.send(LiveMessage::Invalid(format!("line {}: {}", number, e))),
+                }
+            }
+            line.clear();
+        }
+        self.send(LiveMessage::Disconnected);
+    }
+
+    fn forward(&self, message: Message) {
+        match message {
+            Message::State {
+                id,
+                info,
+                props,
+                initial,
+                accepting,
+            } => {
+                let state = State::new(id, info).with_props(props);
+                self.send(LiveMessage::Event(Event::State(state)));
+                if initial {
+                    self.send(LiveMessage::Event(Event::Initial(id)));
+                }
+                if accepting {
+                    self.send(LiveMessage::Event(Event::Accepting(id)));
+                }
+            }
+            Message::Transition {
+                from,
+                to,
+                label,
+                weight,
+            } => {
+                let mut transition = Transition::new(from, to);
+                transition.label = label;
+                transition.weight = weight;
+                self.send(LiveMessage::Event(Event::Transition(transition)));
+            }
+            Message::Done => self.send(LiveMessage::Done),
+        }
+    }
+}
+
+/// Adds the events of a live stream to a graph and its simulation.
+///
+/// States that are referred to before they are sent are added without info,
+/// which is filled in once they arrive.
+#[derive(Default)]
+pub struct LiveGraph {
+    nodes: HashMap<usize, NodeIndex>,
+}
+
+impl LiveGraph {
+    pub fn apply(
+        &mut self,
+        event: Event,
+        graph: &mut Graph<State, Transition, Directed>,
+        sim: &mut Simulation<State, f32>,
+    ) {
+        match event {
+            Event::State(state) => match self.nodes.get(&state.index) {
+                Some(idx) => update(graph, sim, *idx, |old| {
+                    old.info = state.info.clone();
+                    old.props = state.props.clone();
+                }),
+                None => {
+                    self.add(graph, sim, state);
+                }
+            },
+            Event::Transition(transition) => {
+                let from = self.node(graph, sim, transition.from);
+                let to = self.node(graph, sim, transition.to);
+                // a state is placed next to the first state it is connected to
+                for (idx, other) in [(to, from), (from, to)] {
+                    if idx != other && graph.neighbors_undirected(idx).next().is_none() {
+                        let mut rng = rand::thread_rng();
+                        let offset = Vec2::new(
+                            rng.gen_range(-PLACEMENT_SPREAD..=PLACEMENT_SPREAD),
+                            rng.gen_range(-PLACEMENT_SPREAD..=PLACEMENT_SPREAD),
+                        );
+                        let location = graph[other].location() + offset;
+                        graph[idx].set_location(location);
+                        if let Some(node) = sim.get_graph_mut().node_weight_mut(idx) {
+                            node.location = Vec3::new(location.x, location.y, 0.);
+                        }
+                    }
+                }
+                graph.add_edge(from, to, egui_graphs::Edge::new(transition));
+                sim.get_graph_mut().add_edge(from, to, 1.);
+            }
+            Event::Initial(index) => {
+                let idx = self.node(graph, sim, index);
+                update(graph, sim, idx, |state| state.initial = true);
+            }
+            Event::Accepting(index) => {
+                let idx = self.node(graph, sim, index);
+                update(graph, sim, idx, |state| state.accepting = true);
+            }
+        }
+    }
+
+    /// Marks the states without outgoing transitions that are not accepting
+    /// once the exploration is complete.
+    pub fn finish(
+        &self,
+        graph: &mut Graph<State, Transition, Directed>,
+        sim: &mut Simulation<State, f32>,
+    ) {
+        for idx in self.nodes.values() {
+            let deadlock = graph
+                .neighbors_directed(*idx, Direction::Outgoing)
+                .next()
+                .is_none();
+            update(graph, sim, *idx, |state| {
+                state.deadlock = deadlock && !state.accepting
+            });
+        }
+    }
+
+    fn node(
+        &mut self,
+        graph: &mut Graph<State, Transition, Directed>,
+        sim: &mut Simulation<State, f32>,
+        index: usize,
+    ) -> NodeIndex {
+        match self.nodes.get(&index) {
+            Some(idx) => *idx,
+            None => self.add(graph, sim, State::new(index, String::new())),
+        }
+    }
+
+    fn add(
+        &mut self,
+        graph: &mut Graph<State, Transition, Directed>,
+        sim: &mut Simulation<State, f32>,
+        state: State,
+    ) -> NodeIndex {
+        let mut rng = rand::thread_rng();
+        let location = Vec2::new(
+            rng.gen_range(-SPAWN_SIZE..=SPAWN_SIZE),
+            rng.gen_range(-SPAWN_SIZE..=SPAWN_SIZE),
+        );
+        let index = state.index;
+        let sim_node = fdg_sim::Node::new_with_coords(
+            index.to_string(),
+            state.clone(),
+            Vec3::new(location.x, location.y, 0.),
+        );
+        let idx = graph.add_node(Node::new(location, state));
+        graph[idx] = graph[idx].with_label(idx.index().to_string());
+        // both graphs only grow, so their indices stay the same
+        sim.get_graph_mut().add_node(sim_node);
+        self.nodes.insert(index, idx);
+        idx
+    }
+}
+
+fn update(
+    graph: &mut Graph<State, Transition, Directed>,
+    sim: &mut Simulation<State, f32>,
+    idx: NodeIndex,
+    f: impl Fn(&mut State),
+) {
+    if let Some(mut state) = graph[idx].data().cloned() {
+        f(&mut state);
+        graph[idx].set_data(Some(state));
+    }
+    if let Some(node) = sim.get_graph_mut().node_weight_mut(idx) {
+        f(&mut node.data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::graph::construct_simulation;
+
+    /// Hands out the given chunks one per read, failing the reads of `None`
+    /// like a socket whose read timed out.
+    struct Chunks(VecDeque<Option<&'static str>>);
+
+    impl Read for Chunks {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.pop_front() {
+                Some(Some(chunk)) => {
+                    buf[..chunk.len()].copy_from_slice(chunk.as_bytes());
+                    Ok(chunk.len())
+                }
+                Some(None) => Err(ErrorKind::WouldBlock.into()),
+                None => Ok(0),
+            }
+        }
+    }
+
+    fn apply(events: Vec<Event>) -> (Graph<State, Transition, Directed>, Simulation<State, f32>) {
+        let mut graph = Graph::new();
+        let mut sim = construct_simulation(&graph);
+        let mut live = LiveGraph::default();
+        for event in events {
+            live.apply(event, &mut graph, &mut sim);
+        }
+        live.finish(&mut graph, &mut sim);
+        (graph, sim)
+    }
+
+    fn info(graph: &Graph<State, Transition, Directed>, index: usize) -> String {
+        graph
+            .node_weights()
+            .filter_map(|n| n.data())
+            .find(|state| state.index == index)
+            .map(|state| state.info.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn transition_before_its_states() {
+        let (graph, sim) = apply(vec![
+            Event::Transition(Transition::new(0, 1)),
+            Event::State(State::new(1, "b".to_string())),
+            Event::State(State::new(0, "a".to_string())),
+        ]);
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(info(&graph, 0), "a");
+        assert_eq!(info(&graph, 1), "b");
+        assert_eq!(sim.get_graph().node_count(), 2);
+        assert_eq!(sim.get_graph().edge_count(), 1);
+    }
+
+    #[test]
+    fn duplicate_state_updates_it() {
+        let (graph, sim) = apply(vec![
+            Event::State(State::new(0, "a".to_string())),
+            Event::State(State::new(0, "b".to_string()).with_props(vec!["p".to_string()])),
+        ]);
+        assert_eq!(graph.node_count(), 1);
+        assert_eq!(info(&graph, 0), "b");
+        let node = sim.get_graph().node_weights().next().unwrap();
+        assert_eq!(node.data.info, "b");
+        assert_eq!(node.data.props, ["p"]);
+    }
+
+    #[test]
+    fn done_marks_deadlocks() {
+        let (graph, sim) = apply(vec![
+            Event::Transition(Transition::new(0, 1)),
+            Event::Transition(Transition::new(1, 1)),
+            Event::Transition(Transition::new(0, 2)),
+            Event::Transition(Transition::new(0, 3)),
+            Event::Accepting(3),
+        ]);
+        let deadlocks = graph
+            .node_weights()
+            .filter_map(|n| n.data())
+            .filter(|state| state.deadlock)
+            .map(|state| state.index)
+            .collect::<Vec<_>>();
+        assert_eq!(deadlocks, [2]);
+        let marked = sim
+            .get_graph()
+            .node_weights()
+            .filter(|node| node.data.deadlock)
+            .count();
+        assert_eq!(marked, 1);
+    }
+
+    #[test]
+    fn line_split_by_a_read_timeout() {
+        let (sender, receiver) = unbounded();
+        let worker = Worker {
+            sender,
+            stopped: Arc::new(AtomicBool::new(false)),
+            ctx: egui::Context::default(),
+        };
+        let chunks = Chunks(
+            [
+                Some(r#"{"type": "state", "#),
+                None,
+                Some(r#""id": 3, "initial": true}"#),
+                None,
+                Some("\nnot json\n"),
+                Some("{\"type\": \"done\"}\n"),
+            ]
+            .into(),
+        );
+        worker.serve(BufReader::new(chunks));
+
+        let messages = receiver.try_iter().collect::<Vec<_>>();
+        assert_eq!(messages.len(), 5);
+        assert!(
+            matches!(&messages[0], LiveMessage::Event(Event::State(state)) if state.index == 3)
+        );
+        assert!(matches!(messages[1], LiveMessage::Event(Event::Initial(3))));
+        assert!(matches!(&messages[2], LiveMessage::Invalid(e) if e.starts_with("line 2:")));
+        assert!(matches!(messages[3], LiveMessage::Done));
+        assert!(matches!(messages[4], LiveMessage::Disconnected));
+    }
+
+    #[test]
+    fn last_line_without_line_break() {
+        let (sender, receiver) = unbounded();
+        let worker = Worker {
+            sender,
+            stopped: Arc::new(AtomicBool::new(false)),
+            ctx: egui::Context::default(),
+        };
+        let chunks = Chunks(
+            [Some(
+                "{\"type\": \"state\", \"id\": 1}\n{\"type\": \"done\"}",
+            )]
+            .into(),
+        );
+        worker.serve(BufReader::new(chunks));
+
+        let messages = receiver.try_iter().collect::<Vec<_>>();
+        assert_eq!(messages.len(), 3);
+        assert!(matches!(messages[1], LiveMessage::Done));
+        assert!(matches!(messages[2], LiveMessage::Disconnected));
+    }
+}
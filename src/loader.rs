@@ -0,0 +1,361 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use crossbeam::channel::{unbounded, Receiver, Sender};
+use egui_graphs::Graph;
+use fdg_sim::Simulation;
+use petgraph::Directed;
+
+use crate::graph::{
+    construct_simulation, state::State, transition::Transition, validation::ValidationReport,
+    StateSpace,
+};
+
+/// Number of bytes read between two progress reports.
+const REPORT_INTERVAL: u64 = 1 << 20;
+
+/// Turns the content of a file into a state space, describing failures as text.
+pub type TranslateFn = Box<dyn FnOnce(&str) -> Result<StateSpace, String> + Send>;
+
+/// Like [`TranslateFn`], but reads the input while translating it.
+pub type StreamTranslateFn = Box<dyn FnOnce(&mut dyn BufRead) -> Result<StateSpace, String> + Send>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadStage {
+    Reading,
+    /// Reading and translating at once.
+    Streaming,
+    Translating,
+    Validating,
+    Building,
+    Simulating,
+}
+
+impl LoadStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoadStage::Reading => "reading file",
+            LoadStage::Streaming => "reading and translating",
+            LoadStage::Translating => "translating",
+            LoadStage::Validating => "validating",
+            LoadStage::Building => "building graph",
+            LoadStage::Simulating => "preparing layout",
+        }
+    }
+
+    /// Share of the overall progress at which the stage starts and ends.
+    fn range(&self) -> (f32, f32) {
+        match self {
+            LoadStage::Reading => (0., 0.4),
+            LoadStage::Streaming => (0., 0.7),
+            LoadStage::Translating => (0.4, 0.7),
+            LoadStage::Validating => (0.7, 0.75),
+            LoadStage::Building => (0.75, 0.9),
+            LoadStage::Simulating => (0.9, 1.),
+        }
+    }
+}
+
+pub struct LoadedGraph {
+    pub graph: Graph<State, Transition, Directed>,
+    pub sim: Simulation<State, f32>,
+    /// Problems that did not prevent loading, such as unreachable states.
+    pub report: ValidationReport,
+}
+
+pub enum LoadMessage {
+    /// The worker entered `stage` and reached the overall `progress`.
+    Progress(LoadStage, f32),
+    /// Number of bytes read so far.
+    BytesRead(u64),
+    Loaded(Box<LoadedGraph>),
+    /// The state space has to be repaired before it can be shown.
+    Invalid(ValidationReport, Box<StateSpace>),
+    Failed(String),
+}
+
+/// Loads a state space on a worker thread.
+pub struct LoadTask {
+    /// File the state space is read from, if any.
+    pub path: Option<PathBuf>,
+    pub stage: LoadStage,
+    pub progress: f32,
+    pub bytes_read: u64,
+    receiver: Receiver<LoadMessage>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl LoadTask {
+    /// Reads `path` and translates it with `translate`.
+    pub fn open(path: PathBuf, translate: TranslateFn, ctx: egui::Context) -> Self {
+        let worker_path = path.clone();
+        Self::spawn(Some(path), ctx, move |worker| {
+            let content = worker.read(&worker_path)?;
+            worker.progress(LoadStage::Translating, 0.)?;
+            let state_space =
+                translate(&content).map_err(|e| format!("{}: {}", worker_path.display(), e))?;
+            worker.build(state_space)
+        })
+    }
+
+    /// Translates `path` with `translate` while reading it.
+    pub fn open_stream(path: PathBuf, translate: StreamTranslateFn, ctx: egui::Context) -> Self {
+        let worker_path = path.clone();
+        Self::spawn(Some(path), ctx, move |worker| {
+            let describe = |e: io::Error| format!("{}: {}", worker_path.display(), e);
+            let file = File::open(&worker_path).map_err(describe)?;
+            let len = file.metadata().map_err(describe)?.len();
+            let state_space = worker.stream(file, Some(len), translate, &worker_path)?;
+            worker.build(state_space)
+        })
+    }
+
+    /// Translates the standard input with `translate` while reading it.
+    pub fn stdin(translate: StreamTranslateFn, ctx: egui::Context) -> Self {
+        Self::spawn(None, ctx, move |worker| {
+            let state_space = worker.stream(io::stdin(), None, translate, Path::new("<stdin>"))?;
+            worker.build(state_space)
+        })
+    }
+
+    /// Builds the graph of an already translated state space.
+    pub fn build(state_space: StateSpace, ctx: egui::Context) -> Self {
+        Self::spawn(None, ctx, move |worker| worker.build(state_space))
+    }
+
+    fn spawn(
+        path: Option<PathBuf>,
+        ctx: egui::Context,
+        job: impl FnOnce(&Worker) -> Result<LoadMessage, String> + Send + 'static,
+    ) -> Self {
+        let (sender, receiver) = unbounded();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let worker = Worker {
+            sender,
+            cancelled: cancelled.clone(),
+            ctx,
+        };
+        thread::spawn(move || {
+            let message = job(&worker).unwrap_or_else(LoadMessage::Failed);
+            if !worker.cancelled.load(Ordering::Relaxed) {
+                worker.send(message);
+            }
+        });
+
+        Self {
+            path,
+            stage: LoadStage::Reading,
+            progress: 0.,
+            bytes_read: 0,
+            receiver,
+            cancelled,
+        }
+    }
+
+    /// Asks the worker to stop. Its result is discarded.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Applies pending progress updates and returns the result once the worker is done.
+    pub fn poll(&mut self) -> Option<LoadMessage> {
+        for message in self.receiver.try_iter() {
+            match message {
+                LoadMessage::Progress(stage, progress) => {
+                    self.stage = stage;
+                    self.progress = progress;
+                }
+                LoadMessage::BytesRead(bytes) => self.bytes_read = bytes,
+                message => return Some(message),
+            }
+        }
+        None
+    }
+}
+
+struct Worker {
+    sender: Sender<LoadMessage>,
+    cancelled: Arc<AtomicBool>,
+    ctx: egui::Context,
+}
+
+impl Worker {
+    fn send(&self, message: LoadMessage) {
+        // the task is gone if the receiver was dropped
+        let _ = self.sender.send(message);
+        self.ctx.request_repaint();
+    }
+
+    /// Fails if the task was cancelled.
+    fn check_cancelled(&self) -> Result<(), String> {
+        match self.cancelled.load(Ordering::Relaxed) {
+            true => Err("cancelled".to_string()),
+            false => Ok(()),
+        }
+    }
+
+    /// Reports that `fraction` of `stage` is done, failing if the task was cancelled.
+    fn progress(&self, stage: LoadStage, fraction: f32) -> Result<(), String> {
+        self.check_cancelled()?;
+        let (start, end) = stage.range();
+        self.send(LoadMessage::Progress(
+            stage,
+            start + (end - start) * fraction,
+        ));
+        Ok(())
+    }
+
+    fn read(&self, path: &Path) -> Result<String, String> {
+        let describe = |e: io::Error| format!("{}: {}", path.display(), e);
+        let file = File::open(path).map_err(describe)?;
+        let len = file.metadata().map_err(describe)?.len();
+
+        let mut content = String::with_capacity(len as usize);
+        self.reader(file, LoadStage::Reading, Some(len))
+            .read_to_string(&mut content)
+            .map_err(describe)?;
+        Ok(content)
+    }
+
+    fn stream(
+        &self,
+        source: impl Read,
+        len: Option<u64>,
+        translate: StreamTranslateFn,
+        path: &Path,
+    ) -> Result<StateSpace, String> {
+        let mut reader = BufReader::new(self.reader(source, LoadStage::Streaming, len));
+        translate(&mut reader).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    fn reader<R: Read>(
+        &self,
+        inner: R,
+        stage: LoadStage,
+        len: Option<u64>,
+    ) -> ProgressReader<'_, R> {
+        ProgressReader {
+            inner,
+            worker: self,
+            stage,
+            len,
+            read: 0,
+            reported: 0,
+        }
+    }
+
+    fn build(&self, state_space: StateSpace) -> Result<LoadMessage, String> {
+        self.progress(LoadStage::Validating, 0.)?;
+        let report = state_space.validate();
+        if report.is_fatal() {
+            return Ok(LoadMessage::Invalid(report, Box::new(state_space)));
+        }
+
+        self.progress(LoadStage::Building, 0.)?;
+        let graph = state_space.to_graph();
+
+        self.progress(LoadStage::Simulating, 0.)?;
+        let sim = construct_simulation(&graph);
+        self.progress(LoadStage::Simulating, 1.)?;
+
+        Ok(LoadMessage::Loaded(Box::new(LoadedGraph {
+            graph,
+            sim,
+            report,
+        })))
+    }
+}
+
+/// Reports the progress of reading `inner` and stops once the task is cancelled.
+struct ProgressReader<'a, R> {
+    inner: R,
+    worker: &'a Worker,
+    stage: LoadStage,
+    /// Total number of bytes, if known.
+    len: Option<u64>,
+    read: u64,
+    reported: u64,
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // streaming translators only stop reading once the read fails
+        self.worker.check_cancelled().map_err(io::Error::other)?;
+        let read = self.inner.read(buf)?;
+        self.read += read as u64;
+        if read == 0 || self.read - self.reported >= REPORT_INTERVAL {
+            self.reported = self.read;
+            let fraction = match self.len {
+                Some(len) => self.read as f32 / len.max(1) as f32,
+                None => 0.,
+            };
+            self.worker
+                .progress(self.stage, fraction.min(1.))
+                .map_err(io::Error::other)?;
+            self.worker.send(LoadMessage::BytesRead(self.read));
+        }
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::graph::translator::{KripkeJsonTranslator, Translator};
+
+    fn kripke(path: PathBuf) -> LoadTask {
+        LoadTask::open(
+            path,
+            Box::new(|content| KripkeJsonTranslator::translate(content).map_err(|e| e.to_string())),
+            egui::Context::default(),
+        )
+    }
+
+    fn wait(mut task: LoadTask) -> LoadMessage {
+        let start = Instant::now();
+        loop {
+            if let Some(message) = task.poll() {
+                return message;
+            }
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "the task did not finish"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn unreadable_file_fails() {
+        let path = std::env::temp_dir().join("graphew-missing.json");
+        match wait(kripke(path.clone())) {
+            LoadMessage::Failed(message) => {
+                assert!(message.starts_with(&path.display().to_string()))
+            }
+            _ => panic!("expected the load to fail"),
+        }
+    }
+
+    #[test]
+    fn invalid_file_fails() {
+        let path =
+            std::env::temp_dir().join(format!("graphew-invalid-{}.json", std::process::id()));
+        std::fs::write(&path, "{ \"states\": [{ \"name\": \"a\" }] }").unwrap();
+        let message = wait(kripke(path.clone()));
+        std::fs::remove_file(&path).unwrap();
+        match message {
+            LoadMessage::Failed(message) => assert!(message.contains("missing field `id`")),
+            _ => panic!("expected the load to fail"),
+        }
+    }
+}
@@ -8,10 +8,22 @@ fn main() -> eframe::Result<()> {
 
     let mut native_options = eframe::NativeOptions::default();
     native_options.initial_window_size = Some(egui::Vec2::new(800f32, 600f32));
+    // `graphew <slim dump>`, `slim ... | graphew -` or `graphew --live [<source>]`
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
     eframe::run_native(
         "Graphew",
         native_options,
-        Box::new(|cc| Box::new(graphew::MainApp::new(cc))),
+        Box::new(move |cc| {
+            let mut app = graphew::MainApp::new(cc);
+            match &args[..] {
+                [flag, source, ..] if flag == "--live" => app.listen_input(source),
+                // without a source the exploration is read from the standard input
+                [flag] if flag == "--live" => app.listen_input("-"),
+                [input, ..] => app.open_input(input),
+                [] => {}
+            }
+            Box::new(app)
+        }),
     )
 }
 
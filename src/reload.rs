@@ -0,0 +1,302 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crossbeam::channel::{unbounded, Receiver};
+use egui::Vec2;
+use egui_graphs::Graph;
+use fdg_sim::{glam::Vec3, Simulation};
+use petgraph::{stable_graph::NodeIndex, Directed};
+use rand::Rng;
+
+use crate::graph::{state::State, transition::Transition};
+
+/// Time between two looks at the watched file.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Largest distance of a new state from the neighbour it is placed next to.
+const PLACEMENT_SPREAD: f32 = 20.;
+
+/// Watches a file for modifications on a background thread.
+///
+/// A change is reported once the file has stayed the same for one poll
+/// interval, so a file that is still being written is not reported halfway.
+pub struct FileWatcher {
+    pub path: PathBuf,
+    receiver: Receiver<()>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl FileWatcher {
+    pub fn new(path: PathBuf, ctx: egui::Context) -> Self {
+        let (sender, receiver) = unbounded();
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let watched = path.clone();
+        let stop = stopped.clone();
+        let mut last = stamp(&watched);
+        thread::spawn(move || {
+            let mut pending = false;
+            while !stop.load(Ordering::Relaxed) {
+                thread::sleep(POLL_INTERVAL);
+                let current = stamp(&watched);
+                if current != last {
+                    last = current;
+                    pending = true;
+                } else if pending && current.is_some() {
+                    pending = false;
+                    if sender.send(()).is_err() {
+                        break;
+                    }
+                    ctx.request_repaint();
+                }
+            }
+        });
+
+        Self {
+            path,
+            receiver,
+            stopped,
+        }
+    }
+
+    /// Whether the file changed since the last call.
+    pub fn changed(&self) -> bool {
+        self.receiver.try_iter().count() > 0
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Modification time and size of the file at `path`, if it exists.
+fn stamp(path: &Path) -> Option<(Option<SystemTime>, u64)> {
+    fs::metadata(path)
+        .ok()
+        .map(|meta| (meta.modified().ok(), meta.len()))
+}
+
+/// How a reloaded graph differs from the previous one.
+#[derive(Debug, Default)]
+pub struct ReloadDiff {
+    /// Indices of the states that are new or whose info changed.
+    pub added: HashSet<usize>,
+    /// Locations the removed or changed states had in the previous graph.
+    pub removed: Vec<Vec2>,
+}
+
+impl ReloadDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Moves the states of `graph` whose index and info match a state of
+/// `previous` to where that state was, keeping its selection, and places the
+/// other states next to a neighbour that has already been placed.
+pub fn keep_layout(
+    previous: &Graph<State, Transition, Directed>,
+    graph: &mut Graph<State, Transition, Directed>,
+    sim: &mut Simulation<State, f32>,
+) -> ReloadDiff {
+    let mut old = previous
+        .node_weights()
+        .filter_map(|node| node.data().map(|state| (state.index, node)))
+        .collect::<HashMap<_, _>>();
+
+    let mut diff = ReloadDiff::default();
+    let mut placed = HashSet::new();
+    let mut unplaced = vec![];
+    let indices = graph.node_indices().collect::<Vec<_>>();
+    for idx in indices {
+        let Some(state) = graph[idx].data() else {
+            continue;
+        };
+        let index = state.index;
+        match old.get(&index) {
+            Some(node) if node.data().is_some_and(|old| old.info == state.info) => {
+                let (location, selected) = (node.location(), node.selected());
+                old.remove(&index);
+                move_node(graph, sim, idx, location);
+                graph[idx].set_selected(selected);
+                placed.insert(idx);
+            }
+            _ => {
+                diff.added.insert(index);
+                unplaced.push(idx);
+            }
+        }
+    }
+    diff.removed = old.values().map(|node| node.location()).collect();
+
+    // nothing to keep, the simulation lays out the graph from scratch
+    if placed.is_empty() {
+        return diff;
+    }
+
+    // new states may only be connected to other new states, so place them in
+    // rounds until no state has a placed neighbour left
+    let mut rng = rand::thread_rng();
+    loop {
+        let before = unplaced.len();
+        unplaced.retain(|idx| {
+            let Some(neighbour) = graph
+                .neighbors_undirected(*idx)
+                .find(|n| placed.contains(n))
+            else {
+                return true;
+            };
+            let offset = Vec2::new(
+                rng.gen_range(-PLACEMENT_SPREAD..=PLACEMENT_SPREAD),
+                rng.gen_range(-PLACEMENT_SPREAD..=PLACEMENT_SPREAD),
+            );
+            let location = graph[neighbour].location() + offset;
+            move_node(graph, sim, *idx, location);
+            placed.insert(*idx);
+            false
+        });
+        if unplaced.is_empty() || unplaced.len() == before {
+            break;
+        }
+    }
+
+    diff
+}
+
+fn move_node(
+    graph: &mut Graph<State, Transition, Directed>,
+    sim: &mut Simulation<State, f32>,
+    idx: NodeIndex,
+    location: Vec2,
+) {
+    graph[idx].set_location(location);
+    if let Some(node) = sim.get_graph_mut().node_weight_mut(idx) {
+        node.location = Vec3::new(location.x, location.y, 0.);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, time::Instant};
+
+    use super::*;
+    use crate::graph::{construct_simulation, StateSpace};
+
+    fn graph(
+        states: &[(usize, &str)],
+        transitions: &[(usize, usize)],
+    ) -> Graph<State, Transition, Directed> {
+        let space = StateSpace::new(
+            states
+                .iter()
+                .map(|(index, info)| State::new(*index, info.to_string()))
+                .collect(),
+            transitions
+                .iter()
+                .map(|(from, to)| Transition::new(*from, *to))
+                .collect(),
+        );
+        Graph::try_from(&space).unwrap()
+    }
+
+    fn node(graph: &Graph<State, Transition, Directed>, index: usize) -> NodeIndex {
+        graph
+            .node_indices()
+            .find(|idx| graph[*idx].data().is_some_and(|state| state.index == index))
+            .unwrap()
+    }
+
+    #[test]
+    fn keep_layout_of_unchanged_states() {
+        let mut previous = graph(&[(0, "a"), (1, "b"), (2, "c")], &[(0, 1), (1, 2)]);
+        let locations = [
+            Vec2::new(10., 20.),
+            Vec2::new(30., 40.),
+            Vec2::new(50., 60.),
+        ];
+        for (index, location) in locations.iter().enumerate() {
+            let idx = node(&previous, index);
+            previous[idx].set_location(*location);
+        }
+        let kept = node(&previous, 0);
+        previous[kept].set_selected(true);
+
+        // 1 changed its info, 2 is gone and 3 is new
+        let mut reloaded = graph(&[(0, "a"), (1, "B"), (3, "d")], &[(0, 1), (0, 3)]);
+        let mut sim = construct_simulation(&reloaded);
+        let diff = keep_layout(&previous, &mut reloaded, &mut sim);
+
+        let kept = node(&reloaded, 0);
+        assert_eq!(reloaded[kept].location(), locations[0]);
+        assert!(reloaded[kept].selected());
+        let sim_location = sim.get_graph()[kept].location;
+        assert_eq!(Vec2::new(sim_location.x, sim_location.y), locations[0]);
+
+        assert_eq!(diff.added, [1, 3].into());
+        let mut removed = diff.removed.clone();
+        removed.sort_by(|a, b| a.x.total_cmp(&b.x));
+        assert_eq!(removed, [locations[1], locations[2]]);
+        // new states are placed next to the state they are connected to
+        for index in [1, 3] {
+            let distance = reloaded[node(&reloaded, index)].location() - locations[0];
+            assert!(distance.x.abs() <= PLACEMENT_SPREAD && distance.y.abs() <= PLACEMENT_SPREAD);
+        }
+    }
+
+    #[test]
+    fn keep_layout_without_common_states() {
+        let previous = graph(&[(0, "a")], &[]);
+        let mut reloaded = graph(&[(0, "b"), (1, "c")], &[(0, 1)]);
+        let mut sim = construct_simulation(&reloaded);
+        let diff = keep_layout(&previous, &mut reloaded, &mut sim);
+        assert_eq!(diff.added, [0, 1].into());
+        assert_eq!(diff.removed.len(), 1);
+    }
+
+    /// Counts the changes reported within three poll intervals.
+    fn changes(watcher: &FileWatcher) -> usize {
+        let start = Instant::now();
+        let mut changes = 0;
+        while start.elapsed() < POLL_INTERVAL * 3 {
+            changes += watcher.receiver.try_iter().count();
+            thread::sleep(POLL_INTERVAL / 10);
+        }
+        changes
+    }
+
+    #[test]
+    fn one_reload_per_modification() {
+        let path = std::env::temp_dir().join(format!("graphew-watched-{}", std::process::id()));
+        fs::write(&path, "a").unwrap();
+        let watcher = FileWatcher::new(path.clone(), egui::Context::default());
+        assert_eq!(changes(&watcher), 0);
+
+        // only the modification time changes
+        let touch = |seconds| {
+            let time = SystemTime::UNIX_EPOCH + Duration::from_secs(seconds);
+            File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(time)
+                .unwrap();
+        };
+        touch(1_000_000);
+        assert_eq!(changes(&watcher), 1);
+        touch(2_000_000);
+        assert_eq!(changes(&watcher), 1);
+
+        drop(watcher);
+        fs::remove_file(&path).unwrap();
+    }
+}
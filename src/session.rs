@@ -0,0 +1,155 @@
+use std::{collections::BTreeMap, path::Path};
+
+use egui_graphs::Graph;
+use fdg_sim::{
+    force::{Force, Value},
+    glam::Vec3,
+    Simulation,
+};
+use petgraph::Directed;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    graph::{state::State, transition::Transition},
+    settings::{SettingsInteraction, SettingsNavigation, SettingsStyle},
+};
+
+/// Everything needed to reopen a state space the way it was left.
+#[derive(Serialize, Deserialize)]
+pub struct Session {
+    pub source: std::path::PathBuf,
+    /// Name of the format the source is read as.
+    pub format: String,
+    pub positions: Vec<Position>,
+    /// Parameters of the force by name.
+    #[serde(default)]
+    pub force: BTreeMap<String, ForceValue>,
+    #[serde(default)]
+    pub interaction: SettingsInteraction,
+    #[serde(default)]
+    pub navigation: SettingsNavigation,
+    #[serde(default)]
+    pub style: SettingsStyle,
+    /// Indices of the selected states.
+    #[serde(default)]
+    pub selected: Vec<usize>,
+    #[serde(default)]
+    pub dark_mode: bool,
+}
+
+/// Location of the state with the index `state`.
+#[derive(Serialize, Deserialize)]
+pub struct Position {
+    pub state: usize,
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ForceValue {
+    Number(f32),
+    Bool(bool),
+}
+
+impl Session {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let describe = |e: &dyn std::fmt::Display| format!("{}: {}", path.display(), e);
+        let content = std::fs::read_to_string(path).map_err(|e| describe(&e))?;
+        let mut session: Session = serde_json::from_str(&content).map_err(|e| describe(&e))?;
+        // sources are stored relative to the session file where possible
+        if session.source.is_relative() {
+            if let Some(dir) = path.parent() {
+                session.source = dir.join(&session.source);
+            }
+        }
+        Ok(session)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let mut session = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        if let (Some(dir), Ok(source)) = (path.parent(), self.source.canonicalize()) {
+            // the parent of a bare file name is empty
+            let dir = match dir.as_os_str().is_empty() {
+                true => Path::new("."),
+                false => dir,
+            };
+            let dir = dir
+                .canonicalize()
+                .map_err(|e| format!("{}: {}", dir.display(), e))?;
+            if let Ok(relative) = source.strip_prefix(dir) {
+                session["source"] = relative.to_string_lossy().into();
+            }
+        }
+        let content = serde_json::to_string_pretty(&session).map_err(|e| e.to_string())?;
+        std::fs::write(path, content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+/// Current locations of the states of `graph`.
+pub fn positions(graph: &Graph<State, Transition, Directed>) -> Vec<Position> {
+    graph
+        .node_weights()
+        .filter_map(|node| {
+            node.data().map(|state| Position {
+                state: state.index,
+                x: node.location().x,
+                y: node.location().y,
+            })
+        })
+        .collect()
+}
+
+/// Moves the states of `graph` and `sim` to `positions`, keeping states
+/// without a stored position where they are.
+pub fn restore_positions(
+    graph: &mut Graph<State, Transition, Directed>,
+    sim: &mut Simulation<State, f32>,
+    positions: &[Position],
+) {
+    let positions = positions
+        .iter()
+        .map(|position| (position.state, position))
+        .collect::<BTreeMap<_, _>>();
+    let indices = graph.node_indices().collect::<Vec<_>>();
+    for idx in indices {
+        let Some(position) = graph[idx]
+            .data()
+            .and_then(|state| positions.get(&state.index))
+        else {
+            continue;
+        };
+        let location = egui::Vec2::new(position.x, position.y);
+        graph[idx].set_location(location);
+        if let Some(node) = sim.get_graph_mut().node_weight_mut(idx) {
+            node.location = Vec3::new(location.x, location.y, 0.);
+        }
+    }
+}
+
+pub fn force_values(force: &Force<State, f32>) -> BTreeMap<String, ForceValue> {
+    force
+        .dict()
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                Value::Number(value, _) => ForceValue::Number(*value),
+                Value::Bool(value) => ForceValue::Bool(*value),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+/// Sets the parameters of `force` that are in `values`, clamped to their range.
+pub fn restore_force(force: &mut Force<State, f32>, values: &BTreeMap<String, ForceValue>) {
+    for (name, value) in force.dict_mut() {
+        match (value, values.get(name)) {
+            (Value::Number(value, range), Some(ForceValue::Number(saved))) => {
+                *value = saved.clamp(*range.start(), *range.end());
+            }
+            (Value::Bool(value), Some(ForceValue::Bool(saved))) => *value = *saved,
+            _ => {}
+        }
+    }
+}
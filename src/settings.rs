@@ -1,10 +1,18 @@
-#[derive(Default)]
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SettingsInteraction {
     pub folding_enabled: bool,
     pub folding_depth: usize,
     pub selection_depth: i32,
+    /// Whether each strongly connected component is shown as one node.
+    pub condensed: bool,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SettingsNavigation {
     pub zoom_and_pan_enabled: bool,
     pub screen_padding: f32,
@@ -21,10 +29,19 @@ impl Default for SettingsNavigation {
     }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SettingsStyle {
     pub edge_radius_weight: f32,
     pub folded_node_radius_weight: f32,
     pub labels_always: bool,
+    pub edge_labels_always: bool,
+    #[serde(with = "color")]
+    pub color_initial: Color32,
+    #[serde(with = "color")]
+    pub color_accepting: Color32,
+    #[serde(with = "color")]
+    pub color_deadlock: Color32,
 }
 
 impl Default for SettingsStyle {
@@ -33,6 +50,25 @@ impl Default for SettingsStyle {
             edge_radius_weight: 1.,
             folded_node_radius_weight: 2.,
             labels_always: false,
+            edge_labels_always: false,
+            color_initial: Color32::from_rgb(46, 139, 87), // Sea Green
+            color_accepting: Color32::from_rgb(255, 165, 0), // Orange
+            color_deadlock: Color32::from_rgb(220, 20, 60), // Crimson
         }
     }
 }
+
+/// Stores colors as `[r, g, b, a]`.
+mod color {
+    use egui::Color32;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color32, serializer: S) -> Result<S::Ok, S::Error> {
+        color.to_array().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color32, D::Error> {
+        let [r, g, b, a] = <[u8; 4]>::deserialize(deserializer)?;
+        Ok(Color32::from_rgba_premultiplied(r, g, b, a))
+    }
+}
@@ -1 +1,15 @@
 pub mod about;
+pub mod components;
+pub mod condensation;
+pub mod ctl;
+pub mod deadlock;
+pub mod error;
+pub mod format;
+pub mod hypergraph;
+pub mod live;
+pub mod lmntal;
+pub mod overlay;
+pub mod path;
+pub mod progress;
+pub mod repair;
+pub mod sketch;
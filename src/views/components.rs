@@ -0,0 +1,115 @@
+use egui::{ecolor::Hsva, Color32, Grid, ScrollArea, Ui};
+use egui_graphs::Graph;
+use petgraph::{stable_graph::NodeIndex, Directed};
+
+use crate::{
+    analysis::scc::{components, Components},
+    graph::{state::State, transition::Transition},
+};
+
+/// Finds the strongly connected components of the state space and lists them
+/// by size.
+#[derive(Default)]
+pub struct ComponentsPanel {
+    pub components: Option<Components>,
+    /// Whether the states are colored by their component.
+    pub colored: bool,
+    /// Whether to list the components without a cycle too.
+    show_trivial: bool,
+}
+
+impl ComponentsPanel {
+    /// Shows the panel, selecting the states of a component when asked.
+    /// Returns whether the colors of the states need to be updated.
+    pub fn ui(&mut self, ui: &mut Ui, graph: &mut Graph<State, Transition, Directed>) -> bool {
+        let mut recolor = false;
+        ui.horizontal(|ui| {
+            if ui.button("compute").clicked() {
+                self.components = Some(components(graph));
+                recolor = self.colored;
+            }
+            if ui
+                .checkbox(&mut self.colored, "color by component")
+                .changed()
+            {
+                recolor = true;
+            }
+        });
+
+        let Some(components) = &self.components else {
+            return recolor;
+        };
+        let bottom = components.bottom.iter().filter(|b| **b).count();
+        ui.label(format!(
+            "{} components, {} bottom",
+            components.len(),
+            bottom
+        ));
+        ui.checkbox(&mut self.show_trivial, "show single states without loop");
+
+        let mut order = (0..components.len())
+            .filter(|i| self.show_trivial || components.cyclic[*i] || components.bottom[*i])
+            .collect::<Vec<_>>();
+        order.sort_by_key(|i| std::cmp::Reverse(components.members[*i].len()));
+
+        let mut select = None;
+        ScrollArea::vertical()
+            .id_source("components")
+            .max_height(200.)
+            .show(ui, |ui| {
+                Grid::new("components list").num_columns(3).show(ui, |ui| {
+                    for i in order {
+                        let text = format!("{} states", components.members[i].len());
+                        match self.colored {
+                            true => ui.colored_label(component_color(i), text),
+                            false => ui.label(text),
+                        };
+                        match components.bottom[i] {
+                            true => ui.label("bottom"),
+                            false => ui.label(""),
+                        };
+                        if ui.small_button("select").clicked() {
+                            select = Some(i);
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+
+        if let Some(i) = select {
+            let indices = graph.node_indices().collect::<Vec<_>>();
+            indices.into_iter().for_each(|idx| {
+                graph[idx].set_selected(components.component.get(&idx) == Some(&i))
+            });
+        }
+        recolor
+    }
+
+    /// Color of the state at `idx` when the states are colored by component.
+    pub fn color_of(&self, idx: NodeIndex) -> Option<Color32> {
+        match (&self.components, self.colored) {
+            (Some(components), true) => components.component.get(&idx).map(|i| component_color(*i)),
+            _ => None,
+        }
+    }
+
+    /// States in bottom components, if they are colored.
+    pub fn bottom_states(&self) -> Vec<NodeIndex> {
+        match (&self.components, self.colored) {
+            (Some(components), true) => components
+                .members
+                .iter()
+                .zip(&components.bottom)
+                .filter(|(_, bottom)| **bottom)
+                .flat_map(|(members, _)| members.iter().copied())
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+/// Spreads the hues of consecutive components around the color wheel.
+pub fn component_color(component: usize) -> Color32 {
+    let hue = (component as f32 * 0.618_034).fract();
+    Hsva::new(hue, 0.65, 0.85, 1.).into()
+}
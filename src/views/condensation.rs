@@ -0,0 +1,385 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use egui::{Align2, FontId, Rect, Shape, Stroke, Ui, Vec2, Visuals};
+use egui_graphs::{Edge, Graph, Node};
+use fdg_sim::{
+    force::{self, Force},
+    glam::Vec3,
+    ForceGraph, ForceGraphHelper, Simulation, SimulationParameters,
+};
+use petgraph::{stable_graph::NodeIndex, Directed};
+use rand::Rng;
+
+use crate::{
+    analysis::scc::Components,
+    export::svg::{to_svg, SvgView},
+    graph::{state::State, transition::Transition},
+    settings::SettingsStyle,
+};
+
+use super::{
+    components::component_color,
+    overlay::{Disk, ViewTransform, COLOR_BOTTOM, RING_WIDTH},
+    sketch::show_sketch,
+};
+
+const SIMULATION_DT: f32 = 0.035;
+const SCREEN_PADDING: f32 = 0.3;
+/// Largest distance of an expanded state from where its component was.
+const EXPAND_SPREAD: f32 = 20.;
+
+/// Caption of an edge of the condensed graph, the number of transitions it
+/// stands for.
+fn transitions(count: &usize) -> Option<String> {
+    Some(count.to_string())
+}
+
+/// A node of the condensed graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Vertex {
+    /// A collapsed component.
+    Component(usize),
+    /// A state of an expanded component.
+    State(NodeIndex),
+}
+
+/// Shows every strongly connected component of the state space as one node,
+/// which is expanded into its states on double-click.
+pub struct CondensationView {
+    components: Components,
+    expanded: HashSet<usize>,
+    /// Components and the states of expanded components, joined by edges
+    /// labelled with the number of transitions they stand for.
+    graph: Graph<Vertex, usize, Directed>,
+    sim: Simulation<(), f32>,
+    force: Force<(), f32>,
+    /// Whether the state space changed since it was condensed.
+    stale: bool,
+    /// Area and transform the view was last drawn with.
+    shown: (Rect, ViewTransform),
+}
+
+impl CondensationView {
+    pub fn new(states: &Graph<State, Transition, Directed>, components: Components) -> Self {
+        let mut view = Self {
+            components,
+            expanded: HashSet::new(),
+            graph: Graph::default(),
+            sim: Simulation::from_graph(ForceGraph::default(), SimulationParameters::default()),
+            force: force::fruchterman_reingold_weighted(100., 0.95),
+            stale: false,
+            shown: (Rect::NOTHING, ViewTransform::default()),
+        };
+        view.rebuild(states);
+        view
+    }
+
+    /// Notes that the state space changed, e.g. while it is being explored.
+    pub fn mark_stale(&mut self) {
+        self.stale = true;
+    }
+
+    /// Shows the `components` of the changed state space, keeping expanded
+    /// and in place the components that are still there.
+    pub fn refresh(&mut self, states: &Graph<State, Transition, Directed>, components: Components) {
+        // components are numbered anew, so they are found by their states
+        let renumbered = self
+            .components
+            .members
+            .iter()
+            .map(|members| {
+                members
+                    .first()
+                    .and_then(|idx| components.component.get(idx).copied())
+            })
+            .collect::<Vec<_>>();
+        self.expanded = self
+            .expanded
+            .iter()
+            .filter_map(|i| renumbered[*i])
+            .collect();
+        let indices = self.graph.node_indices().collect::<Vec<_>>();
+        for idx in indices {
+            if let Some(Vertex::Component(i)) = self.graph[idx].data().copied() {
+                self.graph[idx].set_data(renumbered[i].map(Vertex::Component));
+            }
+        }
+        self.components = components;
+        self.stale = false;
+        self.rebuild(states);
+    }
+
+    /// Builds the graph for the expanded components again, keeping the
+    /// vertices that stay where they were.
+    fn rebuild(&mut self, states: &Graph<State, Transition, Directed>) {
+        let previous = self
+            .graph
+            .node_weights()
+            .filter_map(|n| n.data().map(|vertex| (*vertex, n.location())))
+            .collect::<HashMap<_, _>>();
+
+        let mut graph = Graph::<Vertex, usize, Directed>::default();
+        let mut force_graph = ForceGraph::<(), f32>::default();
+        let mut vertex_of = HashMap::new();
+        let mut locations = vec![];
+        let mut rng = rand::thread_rng();
+        for (i, members) in self.components.members.iter().enumerate() {
+            let color = component_color(i);
+            if self.expanded.contains(&i) {
+                let around = previous.get(&Vertex::Component(i));
+                for idx in members {
+                    let label = states
+                        .node_weight(*idx)
+                        .and_then(|n| n.data())
+                        .map(|state| state.index.to_string())
+                        .unwrap_or_default();
+                    force_graph.add_force_node(&label, ());
+                    let vertex = Vertex::State(*idx);
+                    let v = graph.add_node(
+                        Node::new(Vec2::ZERO, vertex)
+                            .with_label(label)
+                            .with_color(color),
+                    );
+                    vertex_of.insert(*idx, v);
+                    // states at the same place would not be pushed apart
+                    let offset = Vec2::new(
+                        rng.gen_range(-EXPAND_SPREAD..=EXPAND_SPREAD),
+                        rng.gen_range(-EXPAND_SPREAD..=EXPAND_SPREAD),
+                    );
+                    locations.push(
+                        previous
+                            .get(&vertex)
+                            .copied()
+                            .or(around.map(|loc| *loc + offset)),
+                    );
+                }
+            } else {
+                let label = format!("{} states", members.len());
+                force_graph.add_force_node(&label, ());
+                let v = graph.add_node(
+                    Node::new(Vec2::ZERO, Vertex::Component(i))
+                        .with_label(label)
+                        .with_color(color),
+                );
+                members.iter().for_each(|idx| {
+                    vertex_of.insert(*idx, v);
+                });
+                // a collapsed component goes to the middle of its states
+                let shown = members
+                    .iter()
+                    .filter_map(|idx| previous.get(&Vertex::State(*idx)).copied())
+                    .collect::<Vec<_>>();
+                let middle = match shown.is_empty() {
+                    true => None,
+                    false => {
+                        Some(shown.iter().fold(Vec2::ZERO, |a, b| a + *b) / shown.len() as f32)
+                    }
+                };
+                locations.push(previous.get(&Vertex::Component(i)).copied().or(middle));
+            }
+        }
+
+        let mut edges = BTreeMap::<(NodeIndex, NodeIndex), usize>::new();
+        for idx in states.edge_indices() {
+            let (source, target) = states.edge_endpoints(idx).unwrap();
+            let (Some(a), Some(b)) = (vertex_of.get(&source), vertex_of.get(&target)) else {
+                continue;
+            };
+            // transitions inside a collapsed component are hidden in it
+            if a == b && matches!(graph[*a].data(), Some(Vertex::Component(_))) {
+                continue;
+            }
+            *edges.entry((*a, *b)).or_default() += 1;
+        }
+        for ((a, b), count) in edges {
+            graph.add_edge(a, b, Edge::new(count));
+            // the simulation does not handle loops
+            if a != b {
+                force_graph.add_edge(a, b, 1.);
+            }
+        }
+
+        let mut sim = Simulation::from_graph(force_graph, SimulationParameters::default());
+        for (i, location) in locations.into_iter().enumerate() {
+            if let Some(loc) = location {
+                sim.get_graph_mut()[NodeIndex::new(i)].location = Vec3::new(loc.x, loc.y, 0.);
+            }
+        }
+        self.graph = graph;
+        self.sim = sim;
+    }
+
+    /// Shows the view and returns whether it should be refreshed.
+    pub fn ui(&mut self, ui: &mut Ui, states: &Graph<State, Transition, Directed>) -> bool {
+        self.update_simulation();
+
+        ui.weak("Double-click a component to show its states, and one of them to hide them again.");
+        let mut refresh = false;
+        if self.stale {
+            ui.horizontal(|ui| {
+                ui.label("The state space changed since it was condensed.");
+                refresh = ui.button("refresh").clicked();
+            });
+        }
+
+        // components are drawn behind their nodes, but only once the view is
+        // laid out
+        let slot = ui.painter().add(Shape::Noop);
+
+        let sketch = show_sketch(ui, &mut self.graph, SCREEN_PADDING, transitions);
+        let transform = sketch.transform;
+        self.shown = (sketch.response.rect, transform);
+        let mut shapes = vec![];
+        for disk in self.disks(&transform) {
+            let center = transform.apply(self.graph[disk.node].location());
+            shapes.push(Shape::circle_filled(center, disk.radius, disk.fill));
+            shapes.push(Shape::circle_stroke(center, disk.radius, disk.stroke));
+        }
+        ui.painter().set(slot, Shape::Vec(shapes));
+
+        if self.graph.node_count() == 0 {
+            ui.painter().text(
+                sketch.response.rect.center(),
+                Align2::CENTER_CENTER,
+                "no states",
+                FontId::proportional(14.),
+                ui.visuals().weak_text_color(),
+            );
+        }
+
+        if let Some(id) = sketch.double_clicked {
+            self.toggle(id, states);
+        }
+        refresh
+    }
+
+    /// Renders the view as SVG the way it was last shown.
+    pub fn to_svg(&self, style: &SettingsStyle, visuals: &Visuals) -> String {
+        let (rect, transform) = self.shown;
+        // the sketch labels every node and edge
+        let style = SettingsStyle {
+            labels_always: true,
+            edge_labels_always: true,
+            ..style.clone()
+        };
+        let view = SvgView {
+            rect,
+            transform,
+            selection_depth: 0,
+            style: &style,
+            visuals,
+            overlays: vec![],
+            backdrop: self.disks(&transform),
+        };
+        to_svg(&self.graph, &view, transitions)
+    }
+
+    /// The circles the collapsed components are drawn as, behind their nodes.
+    fn disks(&self, transform: &ViewTransform) -> Vec<Disk> {
+        self.graph
+            .node_indices()
+            .filter_map(|node| {
+                let Some(Vertex::Component(i)) = self.graph[node].data() else {
+                    return None;
+                };
+                let size = self.components.members[*i].len();
+                Some(Disk {
+                    node,
+                    radius: 4. * (size as f32).sqrt() * transform.zoom.max(0.5),
+                    fill: component_color(*i).gamma_multiply(0.3),
+                    stroke: match self.components.bottom[*i] {
+                        true => Stroke::new(RING_WIDTH, COLOR_BOTTOM),
+                        false => Stroke::NONE,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    /// Expands a double-clicked component, or collapses the component of a
+    /// double-clicked state.
+    fn toggle(&mut self, id: NodeIndex, states: &Graph<State, Transition, Directed>) {
+        let toggled = match self.graph.node_weight(id).and_then(|n| n.data()) {
+            Some(Vertex::Component(i)) => self.expanded.insert(*i),
+            Some(Vertex::State(idx)) => match self.components.component.get(idx) {
+                Some(i) => self.expanded.remove(i),
+                None => false,
+            },
+            None => false,
+        };
+        if toggled {
+            self.rebuild(states);
+        }
+    }
+
+    fn update_simulation(&mut self) {
+        self.sim.update_custom(&self.force, SIMULATION_DT);
+
+        let indices = self.graph.node_indices().collect::<Vec<_>>();
+        indices.into_iter().for_each(|idx| {
+            let node = &mut self.graph[idx];
+            let sim_node = &mut self.sim.get_graph_mut()[idx];
+            if node.dragged() {
+                let loc = node.location();
+                sim_node.location = Vec3::new(loc.x, loc.y, 0.);
+            } else {
+                node.set_location(Vec2::new(sim_node.location.x, sim_node.location.y));
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::scc::components;
+
+    use super::*;
+
+    fn add_state(states: &mut Graph<State, Transition, Directed>) -> NodeIndex {
+        let index = states.node_count();
+        states.add_node(Node::new(Vec2::ZERO, State::new(index, String::new())))
+    }
+
+    fn add_transition(
+        states: &mut Graph<State, Transition, Directed>,
+        from: NodeIndex,
+        to: NodeIndex,
+    ) {
+        let transition = Transition::new(from.index(), to.index());
+        states.add_edge(from, to, Edge::new(transition));
+    }
+
+    #[test]
+    fn refresh_keeps_expanded_components() {
+        let mut states = Graph::default();
+        let [a, b, c] = [(); 3].map(|_| add_state(&mut states));
+        add_transition(&mut states, a, b);
+        add_transition(&mut states, b, a);
+        add_transition(&mut states, b, c);
+        let mut view = CondensationView::new(&states, components(&states));
+        let cycle = view.components.component[&a];
+        let component = view
+            .graph
+            .node_indices()
+            .find(|idx| view.graph[*idx].data() == Some(&Vertex::Component(cycle)))
+            .unwrap();
+        view.toggle(component, &states);
+        assert_eq!(view.graph.node_count(), 3);
+
+        // a new state in front of the cycle renumbers the components
+        let d = add_state(&mut states);
+        add_transition(&mut states, d, a);
+        view.mark_stale();
+        view.refresh(&states, components(&states));
+        assert!(!view.stale);
+        assert_eq!(view.expanded, [view.components.component[&a]].into());
+        let vertices = view
+            .graph
+            .node_weights()
+            .filter_map(|n| n.data().copied())
+            .collect::<HashSet<_>>();
+        assert!(vertices.contains(&Vertex::State(a)));
+        assert!(vertices.contains(&Vertex::State(b)));
+        assert_eq!(vertices.len(), 4);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::{BTreeSet, HashSet};
+
+use egui::{Button, Color32, Key, TextEdit, Ui};
+use egui_graphs::Graph;
+use petgraph::{stable_graph::NodeIndex, Directed};
+
+use crate::{
+    analysis::ctl::{check, parse, Formula},
+    graph::{state::State, transition::Transition},
+};
+
+const COLOR_HOLDS: Color32 = Color32::from_rgb(46, 139, 87);
+
+/// Outcome of checking a formula.
+struct Verdict {
+    formula: Formula,
+    satisfying: Vec<NodeIndex>,
+    initial: usize,
+    /// Initial states that do not satisfy the formula.
+    failing: usize,
+}
+
+/// Checks a CTL formula against the state space and highlights the states
+/// satisfying it.
+#[derive(Default)]
+pub struct CtlPanel {
+    formula: String,
+    verdict: Option<Verdict>,
+    error: Option<String>,
+    /// The propositions of the states, listed once per state space.
+    hint: Option<String>,
+}
+
+impl CtlPanel {
+    pub fn ui(&mut self, ui: &mut Ui, graph: &mut Graph<State, Transition, Directed>) {
+        let hint = self.hint.get_or_insert_with(|| {
+            let props = graph
+                .node_weights()
+                .filter_map(|n| n.data())
+                .flat_map(|state| state.props.iter().cloned())
+                .collect::<BTreeSet<_>>();
+            match props.is_empty() {
+                true => "No state has atomic propositions.".to_string(),
+                false => format!(
+                    "Propositions: {}",
+                    props.into_iter().collect::<Vec<_>>().join(", ")
+                ),
+            }
+        });
+
+        let edit = ui
+            .add(
+                TextEdit::singleline(&mut self.formula)
+                    .hint_text("AG (req -> AF ack)")
+                    .code_editor(),
+            )
+            .on_hover_text(hint.as_str());
+        let entered = edit.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+
+        ui.horizontal(|ui| {
+            if ui.button("check").clicked() || entered {
+                self.check(graph);
+            }
+            if ui
+                .add_enabled(self.verdict.is_some(), Button::new("select"))
+                .clicked()
+            {
+                let states = self.states().into_iter().collect::<HashSet<_>>();
+                let indices = graph.node_indices().collect::<Vec<_>>();
+                indices
+                    .into_iter()
+                    .for_each(|idx| graph[idx].set_selected(states.contains(&idx)));
+            }
+            if ui
+                .add_enabled(self.verdict.is_some(), Button::new("clear"))
+                .clicked()
+            {
+                self.verdict = None;
+            }
+        });
+
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        if let Some(verdict) = &self.verdict {
+            ui.monospace(verdict.formula.to_string());
+            match (verdict.initial, verdict.failing) {
+                (0, _) => ui.label("There are no initial states."),
+                (_, 0) => ui.colored_label(COLOR_HOLDS, "Holds in the initial states."),
+                (n, failing) => ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("Fails in {} of {} initial states.", failing, n),
+                ),
+            };
+            ui.label(format!(
+                "{} of {} states satisfy it",
+                verdict.satisfying.len(),
+                graph.node_count()
+            ));
+        }
+    }
+
+    fn check(&mut self, graph: &Graph<State, Transition, Directed>) {
+        let formula = match parse(&self.formula) {
+            Ok(formula) => formula,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                self.verdict = None;
+                return;
+            }
+        };
+        let satisfying = check(graph, &formula);
+        let holds = satisfying.iter().collect::<HashSet<_>>();
+        let initial = graph
+            .node_indices()
+            .filter(|idx| graph[*idx].data().is_some_and(|state| state.initial))
+            .collect::<Vec<_>>();
+        let failing = initial.iter().filter(|idx| !holds.contains(idx)).count();
+        self.error = None;
+        self.verdict = Some(Verdict {
+            formula,
+            satisfying,
+            initial: initial.len(),
+            failing,
+        });
+    }
+
+    /// Drops what was computed for the previous graph, keeping the formula.
+    pub fn graph_changed(&mut self) {
+        self.verdict = None;
+        self.hint = None;
+    }
+
+    /// States satisfying the last formula checked.
+    pub fn states(&self) -> Vec<NodeIndex> {
+        self.verdict
+            .iter()
+            .flat_map(|verdict| verdict.satisfying.iter().copied())
+            .collect()
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+
+use egui::{Button, ScrollArea, Ui};
+use egui_graphs::Graph;
+use petgraph::{stable_graph::NodeIndex, Directed};
+
+use crate::{
+    analysis::{
+        deadlock::{deadlocks, Deadlock},
+        path::Path,
+    },
+    graph::{state::State, transition::Transition},
+};
+
+use super::path::steps;
+
+/// Lists the states without outgoing transitions that are not accepting,
+/// with the shortest path to the chosen one.
+#[derive(Default)]
+pub struct DeadlockPanel {
+    deadlocks: Option<Vec<Deadlock>>,
+    /// Deadlock whose path is shown.
+    chosen: Option<usize>,
+}
+
+impl DeadlockPanel {
+    pub fn ui(&mut self, ui: &mut Ui, graph: &mut Graph<State, Transition, Directed>) {
+        ui.horizontal(|ui| {
+            if ui.button("scan").clicked() {
+                self.deadlocks = Some(deadlocks(graph));
+                self.chosen = None;
+            }
+            let found = self.deadlocks.as_ref().is_some_and(|d| !d.is_empty());
+            if ui.add_enabled(found, Button::new("select all")).clicked() {
+                let states = self.states().into_iter().collect::<HashSet<_>>();
+                let indices = graph.node_indices().collect::<Vec<_>>();
+                indices
+                    .into_iter()
+                    .for_each(|idx| graph[idx].set_selected(states.contains(&idx)));
+            }
+        });
+
+        let Some(deadlocks) = &self.deadlocks else {
+            return;
+        };
+        match deadlocks.len() {
+            0 => ui.label("No deadlocks."),
+            1 => ui.label("1 deadlock"),
+            n => ui.label(format!("{} deadlocks", n)),
+        };
+
+        ScrollArea::vertical()
+            .id_source("deadlocks")
+            .max_height(150.)
+            .show(ui, |ui| {
+                for (i, deadlock) in deadlocks.iter().enumerate() {
+                    let Some(state) = graph.node_weight(deadlock.state).and_then(|n| n.data())
+                    else {
+                        continue;
+                    };
+                    let text = format!("{}: {}", state.index, state.info);
+                    if ui.selectable_label(self.chosen == Some(i), text).clicked() {
+                        self.chosen = match self.chosen == Some(i) {
+                            true => None,
+                            false => Some(i),
+                        };
+                    }
+                }
+            });
+
+        if let Some(deadlock) = self.chosen.and_then(|i| deadlocks.get(i)) {
+            ui.separator();
+            match &deadlock.path {
+                Some(path) => {
+                    ui.label(format!(
+                        "{} transitions from an initial state",
+                        path.edges.len()
+                    ));
+                    steps(ui, "deadlock steps", graph, path);
+                }
+                None => {
+                    ui.label("Not reachable from an initial state.");
+                }
+            }
+        }
+    }
+
+    /// States of the deadlocks found by the last scan.
+    pub fn states(&self) -> Vec<NodeIndex> {
+        self.deadlocks
+            .iter()
+            .flatten()
+            .map(|deadlock| deadlock.state)
+            .collect()
+    }
+
+    /// Path to the chosen deadlock.
+    pub fn path(&self) -> Option<&Path> {
+        let deadlocks = self.deadlocks.as_ref()?;
+        deadlocks.get(self.chosen?)?.path.as_ref()
+    }
+}
@@ -0,0 +1,34 @@
+use egui::{Ui, Window};
+
+pub struct ErrorWindow {
+    pub title: String,
+    pub message: String,
+}
+
+impl ErrorWindow {
+    pub fn new(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        let mut close = false;
+        Window::new(&self.title)
+            .open(open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                self.ui(ui);
+                close = ui.button("OK").clicked();
+            });
+        if close {
+            *open = false;
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        ui.label(&self.message);
+    }
+}
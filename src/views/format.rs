@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use egui::Window;
+
+use crate::graph::translator::registry::{Format, FORMATS};
+
+/// Asks which format to open a file with when it cannot be detected.
+pub struct FormatWindow {
+    pub path: PathBuf,
+    candidates: Vec<&'static Format>,
+    choice: usize,
+}
+
+impl FormatWindow {
+    /// Offers `candidates`, or all formats if there are none.
+    pub fn new(path: PathBuf, candidates: Vec<&'static Format>) -> Self {
+        let candidates = match candidates.is_empty() {
+            true => FORMATS.iter().collect(),
+            false => candidates,
+        };
+        Self {
+            path,
+            candidates,
+            choice: 0,
+        }
+    }
+
+    /// Returns the chosen format once the user confirms it.
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) -> Option<&'static Format> {
+        let mut confirm = false;
+        let mut cancel = false;
+        Window::new("Choose format")
+            .open(open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "The format of {} could not be detected.",
+                    self.path.file_name().unwrap_or_default().to_string_lossy()
+                ));
+                for (i, format) in self.candidates.iter().enumerate() {
+                    ui.radio_value(&mut self.choice, i, format.name);
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    confirm = ui.button("Open").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+
+        if cancel {
+            *open = false;
+        }
+        if !confirm {
+            return None;
+        }
+        *open = false;
+        self.candidates.get(self.choice).copied()
+    }
+}
@@ -0,0 +1,229 @@
+use egui::{Align2, Color32, FontId, Id, Rect, Rounding, Shape, Stroke, Vec2, Window};
+use egui_graphs::{Edge, Graph, Node};
+use fdg_sim::{
+    force::{self, Force},
+    glam::Vec3,
+    ForceGraph, ForceGraphHelper, Simulation, SimulationParameters,
+};
+use std::sync::Arc;
+
+use petgraph::{stable_graph::NodeIndex, Undirected};
+
+use crate::graph::lmntal::Process;
+
+use super::sketch::show_sketch;
+
+const SIMULATION_DT: f32 = 0.035;
+const SCREEN_PADDING: f32 = 0.3;
+/// Space between a membrane and the atoms and membranes in it.
+const MEMBRANE_PADDING: f32 = 12.;
+const COLOR_HYPERLINK: Color32 = Color32::from_rgb(230, 150, 30);
+const COLOR_MEMBRANE: Color32 = Color32::from_rgb(100, 149, 237);
+
+/// A membrane drawn around the vertices in it.
+struct Group {
+    membrane: usize,
+    /// Vertices in the membrane and in the membranes inside it.
+    vertices: Vec<NodeIndex>,
+    /// Node of the membrane in the simulation, which keeps its vertices
+    /// together and marks where an empty membrane is.
+    node: NodeIndex,
+    /// Number of levels of membranes inside it.
+    height: usize,
+}
+
+/// Shows the atoms of an LMNtal state as nodes, its links as edges and its
+/// membranes as boxes around them.
+pub struct HypergraphWindow {
+    /// Index of the state shown.
+    pub state: usize,
+    process: Arc<Process>,
+    /// Atoms and the hubs joining the ends of hyperlinks, described by
+    /// their links.
+    graph: Graph<String, usize, Undirected>,
+    sim: Simulation<(), f32>,
+    force: Force<(), f32>,
+    groups: Vec<Group>,
+}
+
+impl HypergraphWindow {
+    pub fn new(state: usize, process: Arc<Process>) -> Self {
+        let mut graph = Graph::<String, usize, Undirected>::default();
+        let mut force_graph = ForceGraph::<(), f32>::default();
+        let mut membrane_of = vec![];
+
+        for (i, atom) in process.atoms.iter().enumerate() {
+            let description = process.describe_atom(i);
+            graph.add_node(Node::new(Vec2::ZERO, description).with_label(atom.name.clone()));
+            force_graph.add_force_node(&atom.name, ());
+            membrane_of.push(atom.membrane);
+        }
+        for (i, link) in process.links.iter().enumerate() {
+            let ends = link.ends.iter().map(|(atom, _)| NodeIndex::new(*atom));
+            match (link.hyperlink, link.ends.as_slice()) {
+                (false, [(a, _), (b, _)]) => {
+                    let (a, b) = (NodeIndex::new(*a), NodeIndex::new(*b));
+                    graph.add_edge(a, b, Edge::new(i));
+                    // the simulation does not handle loops
+                    if a != b {
+                        force_graph.add_edge(a, b, 1.);
+                    }
+                }
+                // free links only have one end in the state
+                (false, [_] | []) => {}
+                _ => {
+                    let hub = graph.add_node(
+                        Node::new(Vec2::ZERO, format!("hyperlink {}", link.name))
+                            .with_label(link.name.clone())
+                            .with_color(COLOR_HYPERLINK),
+                    );
+                    force_graph.add_force_node(&link.name, ());
+                    membrane_of.push(process.atoms[link.ends[0].0].membrane);
+                    for end in ends {
+                        graph.add_edge(hub, end, Edge::new(i));
+                        force_graph.add_edge(hub, end, 1.);
+                    }
+                }
+            }
+        }
+
+        // parents are parsed before their children, so groups are drawn from
+        // the outside in
+        let mut groups = vec![];
+        for membrane in 1..process.membranes.len() {
+            let vertices = graph
+                .node_indices()
+                .filter(|idx| contains(&process, membrane, membrane_of[idx.index()]))
+                .collect::<Vec<_>>();
+            let node = force_graph.add_force_node(&process.membranes[membrane].name, ());
+            vertices.iter().for_each(|idx| {
+                force_graph.add_edge(node, *idx, 1.);
+            });
+            groups.push(Group {
+                membrane,
+                vertices,
+                node,
+                height: height(&process, membrane),
+            });
+        }
+        // nested membranes stay close to their parent
+        for group in &groups {
+            if let Some(parent) = groups
+                .iter()
+                .find(|g| Some(g.membrane) == process.membranes[group.membrane].parent)
+            {
+                force_graph.add_edge(parent.node, group.node, 1.);
+            }
+        }
+
+        Self {
+            state,
+            process,
+            graph,
+            sim: Simulation::from_graph(force_graph, SimulationParameters::default()),
+            force: force::fruchterman_reingold_weighted(100., 0.95),
+            groups,
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        self.update_simulation();
+        Window::new(format!("State {}", self.state))
+            .id(Id::new(("hypergraph", self.state)))
+            .open(open)
+            .default_size(Vec2::splat(400.))
+            .show(ctx, |ui| self.ui(ui));
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        let selected = self
+            .graph
+            .node_weights()
+            .filter(|node| node.selected())
+            .filter_map(|node| node.data().cloned())
+            .collect::<Vec<_>>();
+        match selected.is_empty() {
+            true => ui.weak("Select an atom to see its links."),
+            false => ui.monospace(selected.join(", ")),
+        };
+
+        // membranes are drawn behind the atoms, but only once the view is laid out
+        let slots = self
+            .groups
+            .iter()
+            .map(|_| ui.painter().add(Shape::Noop))
+            .collect::<Vec<_>>();
+
+        let transform = show_sketch(ui, &mut self.graph, SCREEN_PADDING, |_| None).transform;
+        let stroke = Stroke::new(1.5, COLOR_MEMBRANE);
+        let fill = COLOR_MEMBRANE.gamma_multiply(0.12);
+        for (slot, group) in slots.into_iter().zip(&self.groups) {
+            let membrane = self.sim.get_graph()[group.node].location;
+            let mut rect = Rect::from_center_size(
+                transform.apply(Vec2::new(membrane.x, membrane.y)),
+                Vec2::ZERO,
+            );
+            group.vertices.iter().for_each(|idx| {
+                rect.extend_with(transform.apply(self.graph[*idx].location()));
+            });
+            let rect = rect.expand(MEMBRANE_PADDING * (1 + group.height) as f32);
+
+            let name = &self.process.membranes[group.membrane].name;
+            let label = ui.fonts(|fonts| {
+                Shape::text(
+                    fonts,
+                    rect.left_top() + Vec2::new(4., 2.),
+                    Align2::LEFT_TOP,
+                    name,
+                    FontId::proportional(10.),
+                    COLOR_MEMBRANE,
+                )
+            });
+            ui.painter().set(
+                slot,
+                Shape::Vec(vec![
+                    Shape::rect_filled(rect, Rounding::same(8.), fill),
+                    Shape::rect_stroke(rect, Rounding::same(8.), stroke),
+                    label,
+                ]),
+            );
+        }
+    }
+
+    fn update_simulation(&mut self) {
+        self.sim.update_custom(&self.force, SIMULATION_DT);
+
+        let indices = self.graph.node_indices().collect::<Vec<_>>();
+        indices.into_iter().for_each(|idx| {
+            let node = &mut self.graph[idx];
+            let sim_node = &mut self.sim.get_graph_mut()[idx];
+            if node.dragged() {
+                let loc = node.location();
+                sim_node.location = Vec3::new(loc.x, loc.y, 0.);
+            } else {
+                node.set_location(Vec2::new(sim_node.location.x, sim_node.location.y));
+            }
+        });
+    }
+}
+
+/// Whether `inner` is `outer` or inside it.
+fn contains(process: &Process, outer: usize, inner: usize) -> bool {
+    let mut membrane = Some(inner);
+    while let Some(m) = membrane {
+        if m == outer {
+            return true;
+        }
+        membrane = process.membranes[m].parent;
+    }
+    false
+}
+
+/// Number of levels of membranes inside `membrane`.
+fn height(process: &Process, membrane: usize) -> usize {
+    process
+        .children(membrane)
+        .map(|child| 1 + height(process, child))
+        .max()
+        .unwrap_or(0)
+}
@@ -0,0 +1,59 @@
+use egui::Window;
+
+use crate::live::LiveSource;
+
+/// Asks where to listen for a model checker.
+pub struct LiveWindow {
+    source: String,
+    error: Option<String>,
+}
+
+impl Default for LiveWindow {
+    fn default() -> Self {
+        Self {
+            source: "tcp:127.0.0.1:7878".to_string(),
+            error: None,
+        }
+    }
+}
+
+impl LiveWindow {
+    /// Returns the source once the user confirms a valid one.
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) -> Option<LiveSource> {
+        let mut confirm = false;
+        let mut cancel = false;
+        Window::new("Listen for a model checker")
+            .open(open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Listen on `tcp:<address>` or `unix:<path>`.");
+                ui.text_edit_singleline(&mut self.source);
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    confirm = ui.button("Listen").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+
+        if cancel {
+            *open = false;
+        }
+        if !confirm {
+            return None;
+        }
+        match self.source.trim().parse() {
+            Ok(source) => {
+                *open = false;
+                Some(source)
+            }
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+}
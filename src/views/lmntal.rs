@@ -0,0 +1,26 @@
+use egui::{CollapsingHeader, Ui};
+
+use crate::graph::lmntal::Process;
+
+/// Shows the membranes of `process` as a tree of their atoms and rulesets.
+///
+/// `id` tells the trees of different states apart.
+pub fn process_tree(ui: &mut Ui, process: &Process, id: usize) {
+    membrane(ui, process, 0, id);
+}
+
+fn membrane(ui: &mut Ui, process: &Process, index: usize, id: usize) {
+    process.atoms_in(index).for_each(|atom| {
+        ui.monospace(process.describe_atom(atom));
+    });
+    let rulesets = &process.membranes[index].rulesets;
+    if !rulesets.is_empty() {
+        ui.small(rulesets.join(" "));
+    }
+    process.children(index).for_each(|child| {
+        CollapsingHeader::new(format!("{}{{…}}", process.membranes[child].name))
+            .id_source((id, child))
+            .default_open(true)
+            .show(ui, |ui| membrane(ui, process, child, id));
+    });
+}
@@ -0,0 +1,213 @@
+use egui::{Align2, Color32, FontId, Painter, Pos2, Rect, Stroke, Vec2};
+use egui_graphs::Graph;
+use petgraph::{stable_graph::NodeIndex, Directed, EdgeType};
+
+use crate::{
+    analysis::path::Path,
+    graph::{state::State, transition::Transition},
+    reload::ReloadDiff,
+};
+
+const COLOR_ADDED: Color32 = Color32::from_rgb(0, 200, 80);
+const COLOR_REMOVED: Color32 = Color32::from_rgb(230, 50, 50);
+const COLOR_PATH: Color32 = Color32::from_rgb(255, 170, 0);
+pub const COLOR_BOTTOM: Color32 = Color32::from_rgb(150, 60, 220);
+pub const COLOR_SATISFYING: Color32 = Color32::from_rgb(0, 170, 210);
+pub const RING_WIDTH: f32 = 2.5;
+
+/// Maps graph coordinates to screen coordinates the same way the
+/// `GraphView` does when fit to screen is enabled.
+#[derive(Debug, Clone, Copy)]
+pub struct ViewTransform {
+    pub zoom: f32,
+    pub pan: Vec2,
+}
+
+impl Default for ViewTransform {
+    /// Graph coordinates used as screen coordinates.
+    fn default() -> Self {
+        Self {
+            zoom: 1.,
+            pan: Vec2::ZERO,
+        }
+    }
+}
+
+impl ViewTransform {
+    pub fn fit_to_screen<N: Clone, E: Clone, Ty: EdgeType>(
+        g: &Graph<N, E, Ty>,
+        rect: Rect,
+        screen_padding: f32,
+    ) -> Self {
+        let mut min = Pos2::new(f32::MAX, f32::MAX);
+        let mut max = Pos2::new(f32::MIN, f32::MIN);
+        g.node_weights().for_each(|n| {
+            let loc = n.location().to_pos2();
+            min = min.min(loc);
+            max = max.max(loc);
+        });
+
+        // an empty graph or a single node is shown with a default size
+        let mut diag = max - min;
+        if g.node_count() == 0 || diag == Vec2::ZERO {
+            diag = Vec2::new(1., 100.);
+        }
+        let graph_size = diag * (1. + screen_padding);
+        let zoom = (rect.width() / graph_size.x).min(rect.height() / graph_size.y);
+
+        let graph_center = match g.node_count() {
+            0 => Vec2::ZERO,
+            _ => (min.to_vec2() + max.to_vec2()) / 2.,
+        };
+        Self {
+            zoom,
+            pan: rect.center().to_vec2() - graph_center * zoom,
+        }
+    }
+
+    pub fn apply(&self, loc: Vec2) -> Pos2 {
+        (loc * self.zoom + self.pan).to_pos2()
+    }
+}
+
+/// Writes the caption of every transition next to the middle of its edge.
+pub fn draw_edge_labels(
+    painter: &Painter,
+    g: &Graph<State, Transition, Directed>,
+    transform: &ViewTransform,
+    color: Color32,
+) {
+    g.edge_indices().for_each(|idx| {
+        let Some(caption) = g
+            .edge_weight(idx)
+            .and_then(|e| e.data())
+            .and_then(|t| t.caption())
+        else {
+            return;
+        };
+        let (source, target) = g.edge_endpoints(idx).unwrap();
+        let start = transform.apply(g.node_weight(source).unwrap().location());
+        let end = transform.apply(g.node_weight(target).unwrap().location());
+
+        // loops are drawn above their node
+        let pos = match source == target {
+            true => start - Vec2::new(0., 20. * transform.zoom),
+            false => start + (end - start) / 2.,
+        };
+        painter.text(
+            pos,
+            Align2::CENTER_BOTTOM,
+            caption,
+            FontId::proportional(10.),
+            color,
+        );
+    });
+}
+
+/// Rings the states that appeared with a reload and marks where the removed
+/// states were, fading out with `opacity`.
+pub fn draw_reload_diff(
+    painter: &Painter,
+    g: &Graph<State, Transition, Directed>,
+    transform: &ViewTransform,
+    diff: &ReloadDiff,
+    opacity: f32,
+) {
+    let radius = 12. * transform.zoom.max(0.5);
+    let added = Stroke::new(3., COLOR_ADDED.gamma_multiply(opacity));
+    g.node_weights()
+        .filter(|n| n.data().is_some_and(|s| diff.added.contains(&s.index)))
+        .for_each(|n| painter.circle_stroke(transform.apply(n.location()), radius, added));
+
+    let removed = Stroke::new(3., COLOR_REMOVED.gamma_multiply(opacity));
+    diff.removed.iter().for_each(|loc| {
+        let center = transform.apply(*loc);
+        let d = Vec2::splat(radius / 2.);
+        painter.line_segment([center - d, center + d], removed);
+        painter.line_segment(
+            [center + Vec2::new(-d.x, d.y), center + Vec2::new(d.x, -d.y)],
+            removed,
+        );
+        painter.circle_stroke(center, radius, removed);
+    });
+}
+
+/// A highlight drawn over the states of the main view.
+pub enum Overlay<'a> {
+    /// A ring of the color around each of the states.
+    Rings(Vec<NodeIndex>, Color32),
+    /// The states and transitions of a path.
+    Path(&'a Path),
+}
+
+impl Overlay<'_> {
+    pub fn draw(
+        &self,
+        painter: &Painter,
+        g: &Graph<State, Transition, Directed>,
+        transform: &ViewTransform,
+    ) {
+        match self {
+            Overlay::Rings(states, color) => draw_rings(painter, g, transform, states, *color),
+            Overlay::Path(path) => draw_path(painter, g, transform, path),
+        }
+    }
+}
+
+/// A filled circle behind a node, such as a condensed component.
+pub struct Disk {
+    pub node: NodeIndex,
+    /// Radius on the screen.
+    pub radius: f32,
+    pub fill: Color32,
+    pub stroke: Stroke,
+}
+
+/// Line a path is traced with at `zoom`.
+pub fn path_stroke(zoom: f32) -> Stroke {
+    Stroke::new((6. * zoom).clamp(2., 8.), COLOR_PATH.gamma_multiply(0.6))
+}
+
+/// Radius of the rings around states at `zoom`.
+pub fn ring_radius(zoom: f32) -> f32 {
+    (10. * zoom).max(4.)
+}
+
+/// Traces the states and transitions of `path`.
+fn draw_path(
+    painter: &Painter,
+    g: &Graph<State, Transition, Directed>,
+    transform: &ViewTransform,
+    path: &Path,
+) {
+    let stroke = path_stroke(transform.zoom);
+    let points = path
+        .nodes
+        .iter()
+        .filter_map(|idx| g.node_weight(*idx))
+        .map(|n| transform.apply(n.location()))
+        .collect::<Vec<_>>();
+    points
+        .windows(2)
+        .for_each(|segment| painter.line_segment([segment[0], segment[1]], stroke));
+    let radius = ring_radius(transform.zoom);
+    points
+        .iter()
+        .for_each(|point| painter.circle_stroke(*point, radius, stroke));
+}
+
+/// Draws a ring of `color` around each of `states`.
+fn draw_rings(
+    painter: &Painter,
+    g: &Graph<State, Transition, Directed>,
+    transform: &ViewTransform,
+    states: &[NodeIndex],
+    color: Color32,
+) {
+    let radius = ring_radius(transform.zoom);
+    let stroke = Stroke::new(RING_WIDTH, color);
+    states
+        .iter()
+        .filter_map(|idx| g.node_weight(*idx))
+        .for_each(|n| painter.circle_stroke(transform.apply(n.location()), radius, stroke));
+}
@@ -0,0 +1,129 @@
+use egui::{Button, Grid, ScrollArea, Ui};
+use egui_graphs::Graph;
+use petgraph::{stable_graph::NodeIndex, Directed};
+
+use crate::{
+    analysis::path::{shortest_path, Path},
+    graph::{state::State, transition::Transition},
+};
+
+/// Finds the shortest path between two states and lists its steps.
+#[derive(Default)]
+pub struct PathPanel {
+    /// Start of the path, or the initial states if `None`.
+    from: Option<NodeIndex>,
+    to: Option<NodeIndex>,
+    pub path: Option<Path>,
+    /// Why the last search found no path.
+    message: Option<String>,
+}
+
+impl PathPanel {
+    /// Shows the panel, offering the first of the `selected` states as start
+    /// and target.
+    pub fn ui(
+        &mut self,
+        ui: &mut Ui,
+        graph: &Graph<State, Transition, Directed>,
+        selected: &[NodeIndex],
+    ) {
+        let name = |idx: Option<NodeIndex>| {
+            idx.and_then(|idx| graph.node_weight(idx))
+                .and_then(|node| node.data())
+                .map(|state| format!("state {}", state.index))
+        };
+        let selected = selected.first().copied();
+
+        Grid::new("path").num_columns(3).show(ui, |ui| {
+            ui.label("from");
+            ui.label(name(self.from).unwrap_or_else(|| "initial states".to_string()));
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(selected.is_some(), Button::new("selected"))
+                    .clicked()
+                {
+                    self.from = selected;
+                }
+                if ui.button("initial").clicked() {
+                    self.from = None;
+                }
+            });
+            ui.end_row();
+
+            ui.label("to");
+            ui.label(name(self.to).unwrap_or_else(|| "-".to_string()));
+            if ui
+                .add_enabled(selected.is_some(), Button::new("selected"))
+                .clicked()
+            {
+                self.to = selected;
+            }
+            ui.end_row();
+        });
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.to.is_some(), Button::new("find"))
+                .clicked()
+            {
+                self.find(graph);
+            }
+            if ui
+                .add_enabled(self.path.is_some(), Button::new("clear"))
+                .clicked()
+            {
+                self.path = None;
+            }
+        });
+
+        if let Some(message) = &self.message {
+            ui.colored_label(ui.visuals().error_fg_color, message);
+        }
+        if let Some(path) = &self.path {
+            ui.label(format!("{} transitions", path.edges.len()));
+            steps(ui, "path steps", graph, path);
+        }
+    }
+
+    fn find(&mut self, graph: &Graph<State, Transition, Directed>) {
+        let Some(to) = self.to else {
+            return;
+        };
+        let sources = match self.from {
+            Some(from) => vec![from],
+            None => graph
+                .node_indices()
+                .filter(|idx| graph[*idx].data().is_some_and(|state| state.initial))
+                .collect(),
+        };
+        self.path = shortest_path(graph, sources, to);
+        self.message = match self.path {
+            Some(_) => None,
+            None => Some("The target cannot be reached.".to_string()),
+        };
+    }
+}
+
+/// Lists the states of `path` with the transitions between them.
+pub fn steps(ui: &mut Ui, id: &str, graph: &Graph<State, Transition, Directed>, path: &Path) {
+    ScrollArea::vertical()
+        .id_source(id)
+        .max_height(200.)
+        .show(ui, |ui| {
+            for (i, idx) in path.nodes.iter().enumerate() {
+                if i > 0 {
+                    let caption = graph
+                        .edge_weight(path.edges[i - 1])
+                        .and_then(|edge| edge.data())
+                        .and_then(|transition| transition.caption());
+                    match caption {
+                        Some(caption) => ui.small(format!("↓ {}", caption)),
+                        None => ui.small("↓"),
+                    };
+                }
+                if let Some(state) = graph.node_weight(*idx).and_then(|node| node.data()) {
+                    ui.label(format!("{}: {}", state.index, state.info));
+                }
+            }
+        });
+}
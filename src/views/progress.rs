@@ -0,0 +1,59 @@
+use egui::{ProgressBar, Window};
+
+use crate::loader::{LoadMessage, LoadTask};
+
+/// Shows the progress of a background load and lets the user cancel it.
+pub struct ProgressWindow {
+    task: LoadTask,
+}
+
+impl ProgressWindow {
+    pub fn new(task: LoadTask) -> Self {
+        Self { task }
+    }
+
+    pub fn task(&self) -> &LoadTask {
+        &self.task
+    }
+
+    /// Returns the result of the task once it has finished.
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) -> Option<LoadMessage> {
+        let result = self.task.poll();
+
+        let title = match &self.task.path {
+            Some(path) => format!(
+                "Loading {}",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            ),
+            None => "Loading".to_string(),
+        };
+        let mut cancel = false;
+        Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.add(
+                    ProgressBar::new(self.task.progress)
+                        .text(self.task.stage.name())
+                        .animate(true),
+                );
+                if self.task.bytes_read > 0 {
+                    ui.label(format!(
+                        "{:.1} MiB read",
+                        self.task.bytes_read as f64 / (1 << 20) as f64
+                    ));
+                }
+                cancel = ui.button("Cancel").clicked();
+            });
+
+        if cancel {
+            self.task.cancel();
+            *open = false;
+            return None;
+        }
+        if result.is_some() {
+            *open = false;
+        }
+        result
+    }
+}
@@ -0,0 +1,47 @@
+use egui::{ScrollArea, Window};
+
+use crate::graph::{validation::ValidationReport, StateSpace};
+
+/// Asks whether a state space that cannot be shown as it is should be repaired.
+pub struct RepairWindow {
+    report: ValidationReport,
+    state_space: Option<StateSpace>,
+}
+
+impl RepairWindow {
+    pub fn new(report: ValidationReport, state_space: StateSpace) -> Self {
+        Self {
+            report,
+            state_space: Some(state_space),
+        }
+    }
+
+    /// Returns the repaired state space once the user chooses to repair it.
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) -> Option<StateSpace> {
+        let mut repair = false;
+        let mut cancel = false;
+        Window::new("Invalid state space")
+            .open(open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ScrollArea::vertical().max_height(200.).show(ui, |ui| {
+                    ui.label(self.report.to_string());
+                });
+                ui.separator();
+                ui.label("Repairing creates placeholder states for undeclared states and keeps only the first declaration of duplicate states.");
+                ui.horizontal(|ui| {
+                    repair = ui.button("Repair and open").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+
+        if cancel {
+            *open = false;
+        }
+        if !repair {
+            return None;
+        }
+        *open = false;
+        self.state_space.take().map(StateSpace::repair)
+    }
+}
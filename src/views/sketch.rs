@@ -0,0 +1,175 @@
+use std::f32::consts::TAU;
+
+use egui::{Align2, Color32, FontId, Painter, Pos2, Response, Sense, Stroke, Ui, Vec2};
+use egui_graphs::Graph;
+use petgraph::{stable_graph::NodeIndex, EdgeType};
+
+use super::overlay::ViewTransform;
+
+// sizes and colors as drawn by egui_graphs
+const NODE_RADIUS: f32 = 5.;
+const TIP_ANGLE: f32 = TAU / 50.;
+const TIP_SIZE: f32 = 15.;
+const COLOR_SELECTION: Color32 = Color32::from_rgba_premultiplied(0, 255, 127, 153);
+const COLOR_DRAG: Color32 = Color32::from_rgba_premultiplied(240, 128, 128, 153);
+const COLOR_LIGHT: Color32 = Color32::from_rgb(200, 200, 200);
+const COLOR_DARK: Color32 = Color32::from_rgb(128, 128, 128);
+
+/// What happened in a [`show_sketch`].
+pub struct Sketch {
+    pub response: Response,
+    pub transform: ViewTransform,
+    /// The node double-clicked in this frame, if any.
+    pub double_clicked: Option<NodeIndex>,
+}
+
+/// Draws `g` fit to the available space, with labels and without zoom and
+/// pan, writing the `caption` of each edge next to it. Nodes can be dragged
+/// and a click selects one of them.
+///
+/// The small graphs of the side views are drawn this way instead of with a
+/// `GraphView`: all `GraphView`s of egui_graphs 0.7 keep their zoom and pan
+/// in the same place, so a second one would move the main view around.
+pub fn show_sketch<N: Clone, E: Clone, Ty: EdgeType>(
+    ui: &mut Ui,
+    g: &mut Graph<N, E, Ty>,
+    screen_padding: f32,
+    caption: impl Fn(&E) -> Option<String>,
+) -> Sketch {
+    let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+    let transform = ViewTransform::fit_to_screen(g, response.rect, screen_padding);
+    let radius = (NODE_RADIUS * transform.zoom).max(3.);
+    let node_at = |g: &Graph<N, E, Ty>, pos: Option<Pos2>| {
+        let pos = pos?;
+        g.node_indices()
+            .map(|idx| (idx, transform.apply(g[idx].location()).distance(pos)))
+            .filter(|(_, distance)| *distance <= radius * 1.5)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(idx, _)| idx)
+    };
+
+    if response.drag_started() {
+        if let Some(idx) = node_at(g, response.interact_pointer_pos()) {
+            g[idx].set_dragged(true);
+        }
+    }
+    if response.dragged() {
+        let delta = response.drag_delta() / transform.zoom;
+        g.node_weights_mut()
+            .filter(|n| n.dragged())
+            .for_each(|n| n.set_location(n.location() + delta));
+    }
+    if response.drag_released() {
+        g.node_weights_mut().for_each(|n| n.set_dragged(false));
+    }
+    if response.clicked() {
+        let clicked = node_at(g, response.interact_pointer_pos());
+        let indices = g.node_indices().collect::<Vec<_>>();
+        indices
+            .into_iter()
+            .for_each(|idx| g[idx].set_selected(Some(idx) == clicked));
+    }
+    let double_clicked = match response.double_clicked() {
+        true => node_at(g, response.interact_pointer_pos()),
+        false => None,
+    };
+
+    let (node_color, edge_color) = match ui.visuals().dark_mode {
+        true => (COLOR_LIGHT, COLOR_DARK),
+        false => (COLOR_DARK, COLOR_LIGHT),
+    };
+    draw_edges(&painter, g, &transform, radius, edge_color);
+    draw_captions(
+        &painter,
+        g,
+        &transform,
+        radius,
+        caption,
+        ui.visuals().text_color(),
+    );
+    for n in g.node_weights() {
+        let center = transform.apply(n.location());
+        painter.circle_filled(center, radius, n.color().unwrap_or(node_color));
+        let ring = match n {
+            n if n.dragged() => Some(COLOR_DRAG),
+            n if n.selected() => Some(COLOR_SELECTION),
+            _ => None,
+        };
+        if let Some(ring) = ring {
+            painter.circle_stroke(center, radius * 1.5, Stroke::new(radius / 2., ring));
+        }
+        if let Some(label) = n.label() {
+            painter.text(
+                center - Vec2::new(0., radius * 1.5),
+                Align2::CENTER_BOTTOM,
+                label,
+                FontId::proportional(10.),
+                ui.visuals().text_color(),
+            );
+        }
+    }
+
+    Sketch {
+        response,
+        transform,
+        double_clicked,
+    }
+}
+
+fn draw_edges<N: Clone, E: Clone, Ty: EdgeType>(
+    painter: &Painter,
+    g: &Graph<N, E, Ty>,
+    transform: &ViewTransform,
+    radius: f32,
+    color: Color32,
+) {
+    let stroke = Stroke::new(1., color);
+    for idx in g.edge_indices() {
+        let (source, target) = g.edge_endpoints(idx).unwrap();
+        let start = transform.apply(g[source].location());
+        if source == target {
+            // loops are drawn above their node
+            painter.circle_stroke(start - Vec2::new(0., radius * 1.5), radius, stroke);
+            continue;
+        }
+        let end = transform.apply(g[target].location());
+        let dir = (end - start).normalized();
+        let tip = end - dir * radius;
+        painter.line_segment([start + dir * radius, tip], stroke);
+        if Ty::is_directed() {
+            for angle in [TIP_ANGLE, -TIP_ANGLE] {
+                let back = Vec2::angled(dir.angle() + angle) * TIP_SIZE * transform.zoom.min(1.);
+                painter.line_segment([tip, tip - back], stroke);
+            }
+        }
+    }
+}
+
+fn draw_captions<N: Clone, E: Clone, Ty: EdgeType>(
+    painter: &Painter,
+    g: &Graph<N, E, Ty>,
+    transform: &ViewTransform,
+    radius: f32,
+    caption: impl Fn(&E) -> Option<String>,
+    color: Color32,
+) {
+    for idx in g.edge_indices() {
+        let Some(text) = g[idx].data().and_then(&caption) else {
+            continue;
+        };
+        let (source, target) = g.edge_endpoints(idx).unwrap();
+        let start = transform.apply(g[source].location());
+        // loops are labelled above their circle
+        let at = match source == target {
+            true => start - Vec2::new(0., radius * 2.5),
+            false => start + (transform.apply(g[target].location()) - start) / 2.,
+        };
+        painter.text(
+            at,
+            Align2::CENTER_BOTTOM,
+            text,
+            FontId::proportional(10.),
+            color,
+        );
+    }
+}